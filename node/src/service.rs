@@ -6,7 +6,7 @@ use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_finality_grandpa::SharedVoterState;
 use sc_keystore::LocalKeystore;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_service::{error::Error as ServiceError, ChainType, Configuration, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use std::{sync::Arc, time::Duration};
@@ -37,7 +37,7 @@ type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
 pub fn new_partial(
-	config: &Configuration,
+	config: &mut Configuration,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
@@ -62,6 +62,10 @@ pub fn new_partial(
 		return Err(ServiceError::Other("Remote Keystores are not supported.".into()))
 	}
 
+	// The poe pallet writes claim contents into offchain indexing for the node's RPC.
+	// The client reads this setting when it is built, so it must be forced here.
+	config.offchain_worker.indexing_enabled = true;
+
 	let telemetry = config
 		.telemetry_endpoints
 		.clone()
@@ -165,7 +169,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		select_chain,
 		transaction_pool,
 		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+	} = new_partial(&mut config)?;
 
 	if let Some(url) = &config.keystore_remote {
 		match remote_keystore(url) {
@@ -204,6 +208,16 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		})?;
 
 	if config.offchain_worker.enabled {
+		// Give the template pallet's offchain worker a signing key on development chains.
+		if config.chain_spec.chain_type() == ChainType::Development {
+			sp_keystore::SyncCryptoStore::sr25519_generate_new(
+				&*keystore_container.sync_keystore(),
+				node_template_runtime::pallet_template::KEY_TYPE,
+				Some("//Alice"),
+			)
+			.expect("Creating key with account Alice should succeed.");
+		}
+
 		sc_service::build_offchain_workers(
 			&config,
 			task_manager.spawn_handle(),
@@ -218,14 +232,22 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
+	// Always on, forced in `new_partial`; required by the poe claim content RPC.
+	let indexing_enabled = config.offchain_worker.indexing_enabled;
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
+		let backend = backend.clone();
 		let pool = transaction_pool.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				backend: backend.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				indexing_enabled,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
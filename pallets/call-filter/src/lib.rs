@@ -0,0 +1,209 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// 运行时的调用过滤器：暂停指定的pallet或调用, 以及只允许治理和系统调用的紧急安全模式
+// 在runtime中作为frame_system的BaseCallFilter使用, Root origin不受过滤
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::traits::{CallMetadata, Contains, GetCallMetadata, PalletInfoAccess};
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	// pallet名称或调用名称
+	pub type NameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLength>;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		// 允许暂停、恢复调用以及进入、退出安全模式的origin, 如root或议会
+		type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		// 始终允许的调用, 不受暂停和安全模式影响, 应包含系统、共识和治理相关的调用
+		type WhitelistedCalls: Contains<<Self as frame_system::Config>::RuntimeCall>;
+		// pallet名称与调用名称的最大长度
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+	}
+
+	// 被暂停的pallet或调用, key为(pallet名称, 调用名称), 调用名称为None表示暂停整个pallet
+	#[pallet::storage]
+	#[pallet::getter(fn paused)]
+	pub type PausedCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, (NameOf<T>, Option<NameOf<T>>), (), OptionQuery>;
+
+	// 是否处于安全模式, 安全模式下只允许WhitelistedCalls
+	#[pallet::storage]
+	#[pallet::getter(fn safe_mode)]
+	pub type SafeMode<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		// 暂停pallet或调用
+		CallPaused { pallet_name: Vec<u8>, call_name: Option<Vec<u8>> },
+		// 恢复pallet或调用
+		CallUnpaused { pallet_name: Vec<u8>, call_name: Option<Vec<u8>> },
+		// 进入安全模式
+		SafeModeEntered,
+		// 退出安全模式
+		SafeModeExited,
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		// pallet名称或调用名称过长
+		NameTooLong,
+		// 不能暂停本模块, 否则无法恢复
+		CannotPause,
+		// 已经被暂停
+		AlreadyPaused,
+		// 没有被暂停
+		NotPaused,
+		// 已处于安全模式
+		AlreadyInSafeMode,
+		// 不在安全模式
+		NotInSafeMode,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		// 暂停pallet或调用, 名称与construct_runtime!中的pallet名称、调用的函数名一致
+		// 如(b"KittiesModule", Some(b"transfer"))只暂停转移, (b"KittiesModule", None)暂停整个pallet
+		#[pallet::call_index(0)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn pause(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			call_name: Option<Vec<u8>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				pallet_name.as_slice() != <Self as PalletInfoAccess>::name().as_bytes(),
+				Error::<T>::CannotPause
+			);
+
+			let key = Self::paused_key(&pallet_name, &call_name)?;
+			ensure!(!PausedCalls::<T>::contains_key(&key), Error::<T>::AlreadyPaused);
+			PausedCalls::<T>::insert(key, ());
+
+			Self::deposit_event(Event::CallPaused { pallet_name, call_name });
+
+			Ok(())
+		}
+
+		// 恢复被暂停的pallet或调用, 参数需与暂停时一致
+		#[pallet::call_index(1)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn unpause(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			call_name: Option<Vec<u8>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let key = Self::paused_key(&pallet_name, &call_name)?;
+			ensure!(PausedCalls::<T>::contains_key(&key), Error::<T>::NotPaused);
+			PausedCalls::<T>::remove(key);
+
+			Self::deposit_event(Event::CallUnpaused { pallet_name, call_name });
+
+			Ok(())
+		}
+
+		// 进入安全模式, 除WhitelistedCalls外的调用全部被拒绝
+		#[pallet::call_index(2)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn enter_safe_mode(origin: OriginFor<T>) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			ensure!(!SafeMode::<T>::get(), Error::<T>::AlreadyInSafeMode);
+			SafeMode::<T>::put(true);
+
+			Self::deposit_event(Event::SafeModeEntered);
+
+			Ok(())
+		}
+
+		// 退出安全模式, 之前单独暂停的pallet或调用仍保持暂停
+		#[pallet::call_index(3)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn exit_safe_mode(origin: OriginFor<T>) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			ensure!(SafeMode::<T>::get(), Error::<T>::NotInSafeMode);
+			SafeMode::<T>::kill();
+
+			Self::deposit_event(Event::SafeModeExited);
+
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn paused_key(
+			pallet_name: &[u8],
+			call_name: &Option<Vec<u8>>,
+		) -> Result<(NameOf<T>, Option<NameOf<T>>), DispatchError> {
+			let pallet_name =
+				NameOf::<T>::try_from(pallet_name.to_vec()).map_err(|_| Error::<T>::NameTooLong)?;
+			let call_name = call_name
+				.as_ref()
+				.map(|name| NameOf::<T>::try_from(name.clone()))
+				.transpose()
+				.map_err(|_| Error::<T>::NameTooLong)?;
+			Ok((pallet_name, call_name))
+		}
+
+		// 调用是否被暂停：整个pallet被暂停, 或该调用被单独暂停
+		pub fn is_paused(metadata: &CallMetadata) -> bool {
+			// 名称超长的pallet或调用不可能被暂停
+			let pallet_name = match NameOf::<T>::try_from(metadata.pallet_name.as_bytes().to_vec())
+			{
+				Ok(name) => name,
+				Err(_) => return false,
+			};
+			if PausedCalls::<T>::contains_key((pallet_name.clone(), None::<NameOf<T>>)) {
+				return true
+			}
+			match NameOf::<T>::try_from(metadata.function_name.as_bytes().to_vec()) {
+				Ok(call_name) => PausedCalls::<T>::contains_key((pallet_name, Some(call_name))),
+				Err(_) => false,
+			}
+		}
+	}
+}
+
+// 作为BaseCallFilter使用：本模块与白名单中的调用始终允许
+// 安全模式下拒绝其余调用, 否则只拒绝被暂停的调用
+impl<T: Config> Contains<<T as frame_system::Config>::RuntimeCall> for Pallet<T>
+where
+	<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
+{
+	fn contains(call: &<T as frame_system::Config>::RuntimeCall) -> bool {
+		let metadata = call.get_call_metadata();
+		// 本模块的调用不能被过滤, 否则议会等非root origin无法退出安全模式
+		if metadata.pallet_name == <Self as PalletInfoAccess>::name() ||
+			T::WhitelistedCalls::contains(call)
+		{
+			return true
+		}
+		if SafeMode::<T>::get() {
+			return false
+		}
+		!Self::is_paused(&metadata)
+	}
+}
@@ -0,0 +1,160 @@
+use crate::{mock::*, Error, Event};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::{BadOrigin, Dispatchable};
+
+/// 默认不过滤任何调用
+#[test]
+fn calls_are_allowed_by_default() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(remark().dispatch(RuntimeOrigin::signed(1)));
+		assert_ok!(remark_with_event().dispatch(RuntimeOrigin::signed(1)));
+	})
+}
+
+/// 暂停单个调用, 同一pallet的其它调用不受影响
+#[test]
+fn pause_call_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CallFilter::pause(
+			RuntimeOrigin::root(),
+			b"System".to_vec(),
+			Some(b"remark".to_vec())
+		));
+		System::assert_last_event(
+			Event::CallPaused {
+				pallet_name: b"System".to_vec(),
+				call_name: Some(b"remark".to_vec()),
+			}
+			.into(),
+		);
+
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		assert_ok!(remark_with_event().dispatch(RuntimeOrigin::signed(1)));
+
+		// Root origin不受过滤
+		assert_ok!(remark().dispatch(RuntimeOrigin::root()));
+	})
+}
+
+/// 暂停整个pallet, 白名单中的调用仍然允许
+#[test]
+fn pause_pallet_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CallFilter::pause(RuntimeOrigin::root(), b"System".to_vec(), None));
+
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		assert_ok!(remark_with_event().dispatch(RuntimeOrigin::signed(1)));
+	})
+}
+
+/// 恢复被暂停的调用
+#[test]
+fn unpause_works() {
+	new_test_ext().execute_with(|| {
+		let call_name = Some(b"remark".to_vec());
+		assert_ok!(CallFilter::pause(RuntimeOrigin::root(), b"System".to_vec(), call_name.clone()));
+		assert_ok!(CallFilter::unpause(
+			RuntimeOrigin::root(),
+			b"System".to_vec(),
+			call_name.clone()
+		));
+		System::assert_last_event(
+			Event::CallUnpaused { pallet_name: b"System".to_vec(), call_name }.into(),
+		);
+
+		assert_ok!(remark().dispatch(RuntimeOrigin::signed(1)));
+	})
+}
+
+/// 暂停、恢复失败 - 重复暂停、未暂停、名称过长或暂停本模块
+#[test]
+fn pause_and_unpause_failed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CallFilter::unpause(RuntimeOrigin::root(), b"System".to_vec(), None),
+			Error::<Test>::NotPaused
+		);
+
+		assert_ok!(CallFilter::pause(RuntimeOrigin::root(), b"System".to_vec(), None));
+		assert_noop!(
+			CallFilter::pause(RuntimeOrigin::root(), b"System".to_vec(), None),
+			Error::<Test>::AlreadyPaused
+		);
+
+		assert_noop!(
+			CallFilter::pause(RuntimeOrigin::root(), vec![b'a'; 33], None),
+			Error::<Test>::NameTooLong
+		);
+		assert_noop!(
+			CallFilter::pause(RuntimeOrigin::root(), b"CallFilter".to_vec(), None),
+			Error::<Test>::CannotPause
+		);
+	})
+}
+
+/// 安全模式下只允许白名单与本模块的调用
+#[test]
+fn safe_mode_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CallFilter::enter_safe_mode(RuntimeOrigin::root()));
+		System::assert_last_event(Event::SafeModeEntered.into());
+		assert!(CallFilter::safe_mode());
+
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		assert_ok!(remark_with_event().dispatch(RuntimeOrigin::signed(1)));
+
+		// 本模块的调用不被过滤, 由PauseOrigin决定是否允许
+		let exit = RuntimeCall::CallFilter(crate::Call::exit_safe_mode {});
+		assert_noop!(exit.clone().dispatch(RuntimeOrigin::signed(1)), BadOrigin);
+
+		assert_ok!(exit.dispatch(RuntimeOrigin::root()));
+		System::assert_last_event(Event::SafeModeExited.into());
+		assert_ok!(remark().dispatch(RuntimeOrigin::signed(1)));
+	})
+}
+
+/// 退出安全模式后, 单独暂停的调用仍保持暂停
+#[test]
+fn exit_safe_mode_keeps_paused_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CallFilter::pause(
+			RuntimeOrigin::root(),
+			b"System".to_vec(),
+			Some(b"remark".to_vec())
+		));
+		assert_ok!(CallFilter::enter_safe_mode(RuntimeOrigin::root()));
+		assert_ok!(CallFilter::exit_safe_mode(RuntimeOrigin::root()));
+
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+	})
+}
+
+/// 进入、退出安全模式失败 - 重复进入、未进入或origin不符
+#[test]
+fn safe_mode_failed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(CallFilter::enter_safe_mode(RuntimeOrigin::signed(1)), BadOrigin);
+		assert_noop!(
+			CallFilter::exit_safe_mode(RuntimeOrigin::root()),
+			Error::<Test>::NotInSafeMode
+		);
+
+		assert_ok!(CallFilter::enter_safe_mode(RuntimeOrigin::root()));
+		assert_noop!(
+			CallFilter::enter_safe_mode(RuntimeOrigin::root()),
+			Error::<Test>::AlreadyInSafeMode
+		);
+	})
+}
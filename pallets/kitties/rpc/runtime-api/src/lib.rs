@@ -0,0 +1,38 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_kitties::{KittyInfo, KITTIES_PAGE_SIZE, MAX_KITTIES_PAGE_SIZE};
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+	{
+		/// Get a kitty with its owner and metadata.
+		fn kitty(kitty_id: KittyIndex) -> Option<KittyInfo<AccountId, KittyIndex>>;
+		/// Get page `page` (zero-based) of the kitties owned by `owner` in storage order.
+		///
+		/// `page_size` defaults to `KITTIES_PAGE_SIZE` and is capped at `MAX_KITTIES_PAGE_SIZE`.
+		/// Pages are counted from the kitty after `start_after` when it is given. Passing the id
+		/// of the last returned kitty with page 0 reads only one page of storage, while a page
+		/// number alone skips over all earlier pages.
+		fn kitties_owned_by(
+			owner: AccountId,
+			page: u32,
+			page_size: Option<u32>,
+			start_after: Option<KittyIndex>,
+		) -> Vec<KittyInfo<AccountId, KittyIndex>>;
+		/// Get a page of existing kitties in storage order, paged like `kitties_owned_by`.
+		fn kitties_listed(
+			page: u32,
+			page_size: Option<u32>,
+			start_after: Option<KittyIndex>,
+		) -> Vec<KittyInfo<AccountId, KittyIndex>>;
+		/// Number of existing kitties.
+		fn total_supply() -> u32;
+	}
+}
@@ -0,0 +1,177 @@
+//! RPC interface for the kitties pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+pub use pallet_kitties_rpc_runtime_api::KittiesApi as KittiesRuntimeApi;
+use pallet_kitties_rpc_runtime_api::KittyInfo;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+/// A kitty as returned over RPC, with name and metadata URI decoded as UTF-8.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcKitty<AccountId, KittyIndex> {
+	pub id: KittyIndex,
+	pub owner: AccountId,
+	pub dna: Bytes,
+	pub name: String,
+	pub metadata_uri: String,
+}
+
+impl<AccountId, KittyIndex> From<KittyInfo<AccountId, KittyIndex>>
+	for RpcKitty<AccountId, KittyIndex>
+{
+	fn from(info: KittyInfo<AccountId, KittyIndex>) -> Self {
+		RpcKitty {
+			id: info.id,
+			owner: info.owner,
+			dna: info.dna.to_vec().into(),
+			name: String::from_utf8_lossy(&info.name).into_owned(),
+			metadata_uri: String::from_utf8_lossy(&info.metadata_uri).into_owned(),
+		}
+	}
+}
+
+#[rpc(client, server)]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex> {
+	#[method(name = "kitties_get")]
+	fn get(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<RpcKitty<AccountId, KittyIndex>>>;
+
+	/// Page `page` (zero-based) of the kitties owned by `owner`, `page_size` kitties per page
+	/// (default 20, at most 100). An empty result means there are no more.
+	///
+	/// For large collections pass the id of the last returned kitty as `start_after` with
+	/// page 0 instead, so earlier pages are not scanned again.
+	#[method(name = "kitties_ownedBy")]
+	fn owned_by(
+		&self,
+		owner: AccountId,
+		page: u32,
+		page_size: Option<u32>,
+		start_after: Option<KittyIndex>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<RpcKitty<AccountId, KittyIndex>>>;
+
+	/// A page of all existing kitties, paged like `kitties_ownedBy`.
+	#[method(name = "kitties_listed")]
+	fn listed(
+		&self,
+		page: u32,
+		page_size: Option<u32>,
+		start_after: Option<KittyIndex>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<RpcKitty<AccountId, KittyIndex>>>;
+
+	#[method(name = "kitties_totalSupply")]
+	fn total_supply(&self, at: Option<BlockHash>) -> RpcResult<u32>;
+}
+
+/// Provides RPC methods to query kitties.
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	/// Creates a new instance of the Kitties RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query kitties.",
+		Some(format!("{:?}", e)),
+	))
+	.into()
+}
+
+impl<C, Block, AccountId, KittyIndex>
+	KittiesApiServer<<Block as BlockT>::Hash, AccountId, KittyIndex> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex>,
+	AccountId: Codec + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+	KittyIndex: Codec + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+	fn get(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<RpcKitty<AccountId, KittyIndex>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let kitty = api.kitty(at, kitty_id).map_err(runtime_error)?;
+		Ok(kitty.map(Into::into))
+	}
+
+	fn owned_by(
+		&self,
+		owner: AccountId,
+		page: u32,
+		page_size: Option<u32>,
+		start_after: Option<KittyIndex>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<RpcKitty<AccountId, KittyIndex>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let kitties = api
+			.kitties_owned_by(at, owner, page, page_size, start_after)
+			.map_err(runtime_error)?;
+		Ok(kitties.into_iter().map(Into::into).collect())
+	}
+
+	fn listed(
+		&self,
+		page: u32,
+		page_size: Option<u32>,
+		start_after: Option<KittyIndex>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<RpcKitty<AccountId, KittyIndex>>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let kitties =
+			api.kitties_listed(at, page, page_size, start_after).map_err(runtime_error)?;
+		Ok(kitties.into_iter().map(Into::into).collect())
+	}
+
+	fn total_supply(&self, at: Option<Block::Hash>) -> RpcResult<u32> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.total_supply(at).map_err(runtime_error)
+	}
+}
@@ -0,0 +1,115 @@
+//! 为kitties实现frame_support中通用的nonfungible及nonfungibles接口，
+//! 所有kitty视为同一个collection，CollectionId为`()`。
+
+use super::*;
+use frame_support::{
+	dispatch::DispatchResult,
+	ensure,
+	traits::{
+		tokens::{nonfungible, nonfungibles},
+		Get, ReservableCurrency,
+	},
+};
+use sp_std::prelude::*;
+
+impl<T: Config> nonfungible::Inspect<T::AccountId> for Pallet<T> {
+	type ItemId = T::KittyIndex;
+
+	fn owner(item: &Self::ItemId) -> Option<T::AccountId> {
+		Self::kitty_owner(item)
+	}
+
+	// 支持的属性: dna、name、metadata_uri
+	fn attribute(item: &Self::ItemId, key: &[u8]) -> Option<Vec<u8>> {
+		match key {
+			b"dna" => Self::kitties(item).map(|kitty| kitty.0.to_vec()),
+			b"name" => Self::metadata_of(item).map(|metadata| metadata.name.into_inner()),
+			b"metadata_uri" =>
+				Self::metadata_of(item).map(|metadata| metadata.metadata_uri.into_inner()),
+			_ => None,
+		}
+	}
+
+	fn can_transfer(item: &Self::ItemId) -> bool {
+		Kitties::<T>::contains_key(item) &&
+			!Leases::<T>::contains_key(item) &&
+			!ActiveBattles::<T>::contains_key(item)
+	}
+}
+
+impl<T: Config> nonfungible::Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(item: &Self::ItemId, destination: &T::AccountId) -> DispatchResult {
+		let kitty = Self::get_kitty(*item).map_err(|_| Error::<T>::InvalidKittyId)?;
+		let owner = Self::kitty_owner(item).ok_or(Error::<T>::InvalidKittyId)?;
+
+		Self::do_transfer(&owner, destination, *item, kitty)
+	}
+}
+
+impl<T: Config> nonfungible::Mutate<T::AccountId> for Pallet<T> {
+	// 铸造时同create一样需要质押token, dna随机生成
+	fn mint_into(item: &Self::ItemId, who: &T::AccountId) -> DispatchResult {
+		ensure!(!Kitties::<T>::contains_key(item), Error::<T>::KittyAlreadyExists);
+		let kitty = Kitty(Self::random_value(who));
+
+		T::Currency::reserve(who, T::KittyReserve::get())
+			.map_err(|_| Error::<T>::TokenNotEnough)?;
+		Self::do_mint(who, *item, kitty.clone())?;
+
+		Self::deposit_event(Event::KittyCreated(who.clone(), *item, kitty));
+		Ok(())
+	}
+
+	fn burn(item: &Self::ItemId, maybe_check_owner: Option<&T::AccountId>) -> DispatchResult {
+		Self::do_burn(*item, maybe_check_owner)
+	}
+}
+
+impl<T: Config> nonfungibles::Inspect<T::AccountId> for Pallet<T> {
+	type ItemId = T::KittyIndex;
+	type CollectionId = ();
+
+	fn owner(_collection: &Self::CollectionId, item: &Self::ItemId) -> Option<T::AccountId> {
+		<Self as nonfungible::Inspect<T::AccountId>>::owner(item)
+	}
+
+	fn attribute(
+		_collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		key: &[u8],
+	) -> Option<Vec<u8>> {
+		<Self as nonfungible::Inspect<T::AccountId>>::attribute(item, key)
+	}
+
+	fn can_transfer(_collection: &Self::CollectionId, item: &Self::ItemId) -> bool {
+		<Self as nonfungible::Inspect<T::AccountId>>::can_transfer(item)
+	}
+}
+
+impl<T: Config> nonfungibles::Transfer<T::AccountId> for Pallet<T> {
+	fn transfer(
+		_collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		destination: &T::AccountId,
+	) -> DispatchResult {
+		<Self as nonfungible::Transfer<T::AccountId>>::transfer(item, destination)
+	}
+}
+
+impl<T: Config> nonfungibles::Mutate<T::AccountId> for Pallet<T> {
+	fn mint_into(
+		_collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		<Self as nonfungible::Mutate<T::AccountId>>::mint_into(item, who)
+	}
+
+	fn burn(
+		_collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		maybe_check_owner: Option<&T::AccountId>,
+	) -> DispatchResult {
+		<Self as nonfungible::Mutate<T::AccountId>>::burn(item, maybe_check_owner)
+	}
+}
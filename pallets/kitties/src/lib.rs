@@ -9,21 +9,43 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod impl_nonfungibles;
+
+pub mod migrations;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use codec::MaxEncodedLen;
 	use frame_support::{
 		pallet_prelude::{Member, *},
-		traits::{Currency, Randomness, ReservableCurrency},
+		traits::{BalanceStatus, Currency, ExistenceRequirement, Randomness, ReservableCurrency},
 		Parameter,
 	};
 	use frame_system::pallet_prelude::*;
 	use sp_io::hashing::blake2_128;
-	use sp_runtime::traits::{AtLeast32BitUnsigned, Bounded, One};
+	use sp_runtime::traits::{
+		AtLeast32BitUnsigned, Bounded, CheckedAdd, Hash as HashT, One, Saturating, Zero,
+	};
+	use sp_std::prelude::*;
 
 	// 对每个kitty进行标识
 	// type KittyIndex = u32;
 
+	// 分页查询时默认每页的kitty数量
+	pub const KITTIES_PAGE_SIZE: u32 = 20;
+	// 分页查询时调用方可指定的每页kitty数量上限
+	pub const MAX_KITTIES_PAGE_SIZE: u32 = 100;
+
+	// 到期区块已满时顺延登记的最多区块数
+	const LEASE_EXPIRY_SPILL_BLOCKS: u32 = 10;
+
+	// 到期归还单个出借的存储读写数, 与end_lease保持一致
+	// 读: Leases、Kitties、双方KittyAll、LentOut(两次)
+	// 写: Leases、双方KittyAll、LentOut、KittyOwner、双方OwnedKitties、KittyApprovals
+	// 出借期间kitty不能交换, 质押始终由lender承担, 归还时无需处理交换要约及质押
+	const LEASE_END_READS: u64 = 6;
+	const LEASE_END_WRITES: u64 = 8;
+
 	// 为 Storage NextKittyId 设置一个默认值0
 	#[pallet::type_value]
 	pub fn GetDefaultValue<T: Config>() -> T::KittyIndex {
@@ -34,11 +56,121 @@ pub mod pallet {
 	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
 	pub struct Kitty(pub [u8; 16]);
 
+	// Kitty的性别, 由dna第一个字节的奇偶决定
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub enum Gender {
+		Male,
+		Female,
+	}
+
+	impl Kitty {
+		pub fn gender(&self) -> Gender {
+			if self.0[0] % 2 == 0 {
+				Gender::Male
+			} else {
+				Gender::Female
+			}
+		}
+	}
+
 	type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+	// Kitty的名称及链下元数据URI, 押金由设置者depositor质押
+	#[derive(
+		Encode,
+		Decode,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	#[codec(mel_bound())]
+	pub struct KittyMetadata<T: Config> {
+		pub name: BoundedVec<u8, T::MaxNameLength>,
+		pub metadata_uri: BoundedVec<u8, T::MaxMetadataUriLength>,
+		pub depositor: T::AccountId,
+		pub deposit: BalanceOf<T>,
+	}
+
+	// commit_create提交的承诺及提交时的区块
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct MintCommit<Hash, BlockNumber> {
+		pub commitment: Hash,
+		pub committed_at: BlockNumber,
+	}
+
+	// 出借kitty的要约, 由borrower接受后生效
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct LeaseOffer<AccountId, Balance, BlockNumber> {
+		pub lender: AccountId,
+		pub borrower: AccountId,
+		pub fee: Balance,
+		pub duration: BlockNumber,
+	}
+
+	// 出借记录, 到期区块end时kitty自动归还lender
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct Lease<AccountId, Balance, BlockNumber> {
+		pub lender: AccountId,
+		pub borrower: AccountId,
+		pub fee: Balance,
+		pub end: BlockNumber,
+	}
+
+	// 对战, 被挑战的kitty的owner接受后accepted记录其账户及接受时的区块
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct Battle<AccountId, Balance, BlockNumber, KittyIndex> {
+		pub challenger: AccountId,
+		pub challenger_kitty: KittyIndex,
+		pub opponent_kitty: KittyIndex,
+		pub wager: Balance,
+		pub accepted: Option<(AccountId, BlockNumber)>,
+	}
+
+	// kitty的对战战绩
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default, TypeInfo, MaxEncodedLen)]
+	pub struct BattleRecord {
+		pub wins: u32,
+		pub losses: u32,
+	}
+
+	// 交换要约期望换得的kitty: 指定的kitty, 或dna满足特征过滤条件的任意kitty
+	// 特征过滤要求 dna & mask == value & mask, 并可限定性别
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub enum SwapTarget<KittyIndex> {
+		Kitty(KittyIndex),
+		Traits { mask: [u8; 16], value: [u8; 16], gender: Option<Gender> },
+	}
+
+	// 交换要约, 提议者补贴的top_up在接受时转给接受者
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct SwapOffer<AccountId, Balance, BlockNumber, KittyIndex> {
+		pub proposer: AccountId,
+		pub target: SwapTarget<KittyIndex>,
+		pub top_up: Balance,
+		pub expires_at: BlockNumber,
+	}
+
+	// 对外查询的Kitty信息
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+	pub struct KittyInfo<AccountId, KittyIndex> {
+		pub id: KittyIndex,
+		pub owner: AccountId,
+		pub dna: [u8; 16],
+		pub name: Vec<u8>,
+		pub metadata_uri: Vec<u8>,
+	}
+
+	// 存储版本，存储结构变更时递增并在migrations中添加对应的迁移
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -58,6 +190,42 @@ pub mod pallet {
 		type KittyReserve: Get<BalanceOf<Self>>;
 		// 用于质押等于资产相关的操作
 		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+		// Kitty名称最大长度
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+		// Kitty元数据URI最大长度
+		#[pallet::constant]
+		type MaxMetadataUriLength: Get<u32>;
+		// 设置元数据时每字节需要质押的token数量
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+		// commit_create之后第RevealDelay个区块在on_initialize中固定揭示使用的随机数
+		// 需要不小于Randomness的回溯区块数, 保证该随机数全部产生于提交之后, 且至少为1
+		#[pallet::constant]
+		type RevealDelay: Get<Self::BlockNumber>;
+		// 可以reveal_create的区块窗口, 超出窗口后承诺过期, 取消时没收质押的token
+		// 因kitty总数达到上限等非用户原因无法揭示的承诺, 取消时退还质押的token
+		#[pallet::constant]
+		type RevealWindow: Get<Self::BlockNumber>;
+		// 出借的最长区块数
+		#[pallet::constant]
+		type MaxLeaseDuration: Get<Self::BlockNumber>;
+		// 同一区块最多到期归还的出借数, 限制on_initialize中的处理量, 超出的顺延到之后的区块
+		#[pallet::constant]
+		type MaxLeasesPerBlock: Get<u32>;
+		// 全网kitty总数上限
+		#[pallet::constant]
+		type MaxTotalKitties: Get<u32>;
+		// 接受挑战之后第BattleDelay个区块在on_initialize中固定结算使用的随机数
+		// 需要不小于Randomness的回溯区块数, 保证该随机数全部产生于接受之后, 且至少为1
+		#[pallet::constant]
+		type BattleDelay: Get<Self::BlockNumber>;
+		// 批量操作一次最多处理的kitty数量
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+		// 交换要约的最长有效区块数
+		#[pallet::constant]
+		type MaxSwapDuration: Get<Self::BlockNumber>;
 	}
 
 	#[pallet::event]
@@ -66,6 +234,53 @@ pub mod pallet {
 		KittyCreated(T::AccountId, T::KittyIndex, Kitty),
 		KittyBred(T::AccountId, T::KittyIndex, Kitty),
 		KittyTransferred(T::AccountId, T::AccountId, T::KittyIndex),
+		// owner, 被授权账户, kitty id
+		KittyApproved(T::AccountId, T::AccountId, T::KittyIndex),
+		// owner, kitty id
+		KittyApprovalCancelled(T::AccountId, T::KittyIndex),
+		// owner, operator, 是否授权
+		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		// owner, kitty id, 名称, 元数据URI
+		KittyMetadataSet(T::AccountId, T::KittyIndex, Vec<u8>, Vec<u8>),
+		KittyMetadataCleared(T::AccountId, T::KittyIndex),
+		KittyBurned(T::AccountId, T::KittyIndex),
+		// 账户, 提交承诺的区块
+		KittyCommitted(T::AccountId, T::BlockNumber),
+		KittyCommitCancelled(T::AccountId),
+		// lender, borrower, kitty id, 租金, 出借区块数
+		LeaseOffered(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		LeaseOfferCancelled(T::AccountId, T::KittyIndex),
+		// lender, borrower, kitty id, 到期区块
+		LeaseStarted(T::AccountId, T::AccountId, T::KittyIndex, T::BlockNumber),
+		// lender, borrower, kitty id
+		LeaseEnded(T::AccountId, T::AccountId, T::KittyIndex),
+		// 挑战者, 对战id, 挑战者kitty, 被挑战kitty, 赌注
+		BattleChallenged(T::AccountId, u32, T::KittyIndex, T::KittyIndex, BalanceOf<T>),
+		// 应战者, 对战id
+		BattleAccepted(T::AccountId, u32),
+		BattleCancelled(T::AccountId, u32),
+		// 对战id, 胜者账户, 胜者kitty, 败者kitty, 胜者实际获得的败者赌注
+		// 败者质押的token在对战期间被扣减时, 实际获得的赌注少于约定的赌注
+		BattleResolved(u32, T::AccountId, T::KittyIndex, T::KittyIndex, BalanceOf<T>),
+		// 批量操作汇总, 每个kitty另有各自的事件
+		// 原owner, 新owner, 转移数量
+		KittiesBatchTransferred(T::AccountId, T::AccountId, u32),
+		// owner, 创建数量
+		KittiesBatchCreated(T::AccountId, u32),
+		// owner, 销毁数量
+		KittiesBatchBurned(T::AccountId, u32),
+		// 提议者, 提议交换的kitty, 期望换得的kitty, 补贴, 到期区块
+		SwapProposed(
+			T::AccountId,
+			T::KittyIndex,
+			SwapTarget<T::KittyIndex>,
+			BalanceOf<T>,
+			T::BlockNumber,
+		),
+		// 提议者, 提议交换的kitty
+		SwapCancelled(T::AccountId, T::KittyIndex),
+		// 提议者, 接受者, 提议者的kitty, 接受者的kitty, 补贴
+		SwapAccepted(T::AccountId, T::AccountId, T::KittyIndex, T::KittyIndex, BalanceOf<T>),
 	}
 
 	#[pallet::error]
@@ -76,6 +291,46 @@ pub mod pallet {
 		KittyIdOverflow,
 		ExceedMaxKittyOwned,
 		TokenNotEnough,
+		NotApproved,
+		ApproveToSelf,
+		NameTooLong,
+		MetadataUriTooLong,
+		KittyAlreadyExists,
+		CommitAlreadyPending,
+		NoPendingCommit,
+		InvalidReveal,
+		RevealTooEarly,
+		RandomnessNotReady,
+		CommitExpired,
+		CommitNotExpired,
+		KittyOnLease,
+		InvalidLeaseDuration,
+		LeaseOfferNotExist,
+		NotBorrower,
+		LeaseNotExist,
+		TooManyLeasesExpiring,
+		MaxTotalKittiesReached,
+		KittyInBattle,
+		BattleWithSelf,
+		BattleNotExist,
+		BattleAlreadyAccepted,
+		BattleNotAccepted,
+		BattleTooEarly,
+		BattleIdOverflow,
+		EmptyBatch,
+		TooManyInBatch,
+		IncompatibleGender,
+		TooCloselyRelated,
+		SwapAlreadyProposed,
+		SwapOfferNotExist,
+		SwapOfferExpired,
+		SwapOfferNotExpired,
+		SwapTargetMismatch,
+		InvalidSwapDuration,
+		SwapWithSelf,
+		KittyCountUnderflow,
+		// 提议者质押的补贴已被削减, 无法足额支付
+		SwapTopUpUnavailable,
 	}
 
 	// 存储KittyId
@@ -85,6 +340,12 @@ pub mod pallet {
 	pub type NextKittyId<T: Config> =
 		StorageValue<_, T::KittyIndex, ValueQuery, GetDefaultValue<T>>;
 
+	// 当前存在的kitty总数, 不包括已销毁的
+	// 已有链上引入该计数时由migrations::v1::MigrateToV1按Kitties回填
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_count)]
+	pub type KittyCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
 	// 存储Kitty的特征属性
 	#[pallet::storage]
 	#[pallet::getter(fn kitties)]
@@ -95,6 +356,18 @@ pub mod pallet {
 	#[pallet::getter(fn kitty_owner)]
 	pub type KittyOwner<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
 
+	// 按账户索引的kitty编号, 与KittyOwner一致(出借中的kitty记在borrower名下), 用于按账户分页查询
+	#[pallet::storage]
+	pub type OwnedKitties<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::KittyIndex,
+		(),
+		OptionQuery,
+	>;
+
 	// 存储一个账户拥有的所有Kitty
 	#[pallet::storage]
 	#[pallet::getter(fn kitty_all)]
@@ -106,9 +379,186 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	// 繁殖出的Kitty的父母
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_parents)]
+	pub type KittyParents<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, (T::KittyIndex, T::KittyIndex)>;
+
+	// 单个Kitty被授权可转移的账户，Kitty转移后清除
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_approval)]
+	pub type KittyApprovals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	// owner授权operator可操作其名下所有Kitty
+	#[pallet::storage]
+	#[pallet::getter(fn operator_approval)]
+	pub type OperatorApprovals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	// Kitty的名称及元数据URI
+	#[pallet::storage]
+	#[pallet::getter(fn metadata_of)]
+	pub type MetadataOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyMetadata<T>>;
+
+	// 等待揭示的铸造承诺, 每个账户同时只能有一个
+	#[pallet::storage]
+	#[pallet::getter(fn pending_mint)]
+	pub type PendingMints<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, MintCommit<T::Hash, T::BlockNumber>>;
+
+	// 出借要约
+	#[pallet::storage]
+	#[pallet::getter(fn lease_offer)]
+	pub type LeaseOffers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		LeaseOffer<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	// 需要在on_initialize中固定随机数的区块及等待使用该随机数的承诺、对战数量
+	#[pallet::storage]
+	pub type SeedRequests<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	// 在对应区块的on_initialize中固定的随机数, 揭示或结算时无论在哪个区块调用都使用它
+	#[pallet::storage]
+	#[pallet::getter(fn block_seed)]
+	pub type BlockSeeds<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, T::Hash>;
+
+	// 出借中的kitty
+	#[pallet::storage]
+	#[pallet::getter(fn lease)]
+	pub type Leases<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		Lease<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	// 每个区块到期需归还的kitty
+	#[pallet::storage]
+	pub type LeaseExpiries<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::KittyIndex, T::MaxLeasesPerBlock>,
+		ValueQuery,
+	>;
+
+	// 账户出借中的kitty数量, 这些kitty在KittyAll中的位置为归还保留
+	#[pallet::storage]
+	#[pallet::getter(fn lent_out)]
+	pub type LentOut<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	// 下一个对战id
+	#[pallet::storage]
+	#[pallet::getter(fn next_battle_id)]
+	pub type NextBattleId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	// 尚未结算的对战
+	#[pallet::storage]
+	#[pallet::getter(fn battle)]
+	pub type Battles<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u32,
+		Battle<T::AccountId, BalanceOf<T>, T::BlockNumber, T::KittyIndex>,
+	>;
+
+	// 参与对战中的kitty及其对战id, 对战结算或取消前不能转移、出借或销毁
+	#[pallet::storage]
+	#[pallet::getter(fn active_battle)]
+	pub type ActiveBattles<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, u32>;
+
+	// 每个kitty的对战战绩
+	#[pallet::storage]
+	#[pallet::getter(fn battle_record)]
+	pub type BattleRecords<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, BattleRecord, ValueQuery>;
+
+	// 交换要约, 以提议者的kitty为键
+	#[pallet::storage]
+	#[pallet::getter(fn swap_offer)]
+	pub type SwapOffers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		SwapOffer<T::AccountId, BalanceOf<T>, T::BlockNumber, T::KittyIndex>,
+	>;
+
+	// 创世时的kitty: (owner, dna), owner需有足够的余额质押
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub kitties: Vec<(T::AccountId, [u8; 16])>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { kitties: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, dna) in &self.kitties {
+				let kitty_id = Pallet::<T>::get_next_id().expect("genesis kitty id overflow");
+				T::Currency::reserve(owner, T::KittyReserve::get())
+					.expect("genesis kitty owner must have enough balance to reserve");
+				Pallet::<T>::do_mint(owner, kitty_id, Kitty(*dna)).expect("invalid genesis kitty");
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 固定本区块的随机数供承诺揭示、对战结算使用
+		// 归还在本区块到期的kitty, 数量受MaxLeasesPerBlock限制
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if SeedRequests::<T>::contains_key(n) {
+				let (seed, _) = T::Randomness::random(b"kitties/seed");
+				BlockSeeds::<T>::insert(n, seed);
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			}
+
+			let expired = LeaseExpiries::<T>::take(n);
+			let count = expired.len() as u64;
+
+			for kitty_id in expired {
+				if let Some(lease) = Leases::<T>::take(kitty_id) {
+					Self::end_lease(kitty_id, lease);
+				}
+			}
+
+			weight.saturating_add(T::DbWeight::get().reads_writes(
+				1 + LEASE_END_READS * count,
+				1 + LEASE_END_WRITES * count,
+			))
+		}
+
+		// 随机数在请求区块的on_initialize中固定, 延迟为0时请求的是已经初始化过的当前区块
+		fn integrity_test() {
+			assert!(!T::RevealDelay::get().is_zero(), "RevealDelay must be at least 1");
+			assert!(!T::BattleDelay::get().is_zero(), "BattleDelay must be at least 1");
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		// 新增
+		// 新增, dna在交易提交时即可被预测, 需要不可预测的dna时使用commit_create和reveal_create
 		#[pallet::call_index(0)]
 		#[pallet::weight(0)]
 		pub fn create(origin: OriginFor<T>) -> DispatchResult {
@@ -122,11 +572,7 @@ pub mod pallet {
 			T::Currency::reserve(&who, T::KittyReserve::get())
 				.map_err(|_| Error::<T>::TokenNotEnough)?;
 
-			Kitties::<T>::insert(kitty_id, &kitty);
-			KittyOwner::<T>::insert(kitty_id, &who);
-			NextKittyId::<T>::set(kitty_id + One::one());
-			KittyAll::<T>::try_mutate(&who, |kitty_vec| kitty_vec.try_push(kitty.clone()))
-				.map_err(|_| Error::<T>::ExceedMaxKittyOwned)?;
+			Self::do_mint(&who, kitty_id, kitty.clone())?;
 
 			// Emit an event.
 			Self::deposit_event(Event::KittyCreated(who, kitty_id, kitty));
@@ -152,6 +598,14 @@ pub mod pallet {
 			let kitty_1 = Self::get_kitty(kitty_id_1).map_err(|_| Error::<T>::InvalidKittyId)?;
 			let kitty_2 = Self::get_kitty(kitty_id_2).map_err(|_| Error::<T>::InvalidKittyId)?;
 
+			// 只能用自己拥有或租用中的kitty繁殖, 出借期间kitty的owner为borrower
+			ensure!(Self::kitty_owner(kitty_id_1) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(Self::kitty_owner(kitty_id_2) == Some(who.clone()), Error::<T>::NotOwner);
+
+			// 父母需为一公一母, 且不能为父子或兄弟姐妹关系
+			ensure!(kitty_1.gender() != kitty_2.gender(), Error::<T>::IncompatibleGender);
+			ensure!(!Self::closely_related(kitty_id_1, kitty_id_2), Error::<T>::TooCloselyRelated);
+
 			// get next id
 			let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
 
@@ -165,11 +619,8 @@ pub mod pallet {
 			}
 			let new_kitty = Kitty(data);
 
-			<Kitties<T>>::insert(kitty_id, &new_kitty);
-			KittyOwner::<T>::insert(kitty_id, &who);
-			NextKittyId::<T>::set(kitty_id + One::one());
-			KittyAll::<T>::try_mutate(&who, |kitty_vec| kitty_vec.try_push(new_kitty.clone()))
-				.map_err(|_| Error::<T>::ExceedMaxKittyOwned)?;
+			Self::do_mint(&who, kitty_id, new_kitty.clone())?;
+			KittyParents::<T>::insert(kitty_id, (kitty_id_1, kitty_id_2));
 
 			Self::deposit_event(Event::KittyCreated(who, kitty_id, new_kitty));
 
@@ -191,38 +642,858 @@ pub mod pallet {
 			// 验证只有自己才能操作自己的owner
 			ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
 
-			// 新Owner质押token
-			T::Currency::reserve(&new_owner, T::KittyReserve::get())
+			Self::do_transfer(&who, &new_owner, kitty_id, exist_kitty)
+		}
+
+		// 授权spender转移指定的kitty, owner或其operator可调用
+		#[pallet::call_index(3)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			spender: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(who == owner || Self::operator_approval(&owner, &who), Error::<T>::NotOwner);
+			ensure!(spender != owner, Error::<T>::ApproveToSelf);
+
+			KittyApprovals::<T>::insert(kitty_id, &spender);
+
+			Self::deposit_event(Event::KittyApproved(owner, spender, kitty_id));
+
+			Ok(())
+		}
+
+		// 授权或取消授权operator操作自己名下的所有kitty
+		#[pallet::call_index(4)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(operator != who, Error::<T>::ApproveToSelf);
+
+			if approved {
+				OperatorApprovals::<T>::insert(&who, &operator, true);
+			} else {
+				OperatorApprovals::<T>::remove(&who, &operator);
+			}
+
+			Self::deposit_event(Event::ApprovalForAll(who, operator, approved));
+
+			Ok(())
+		}
+
+		// 被授权账户或operator代owner转移kitty
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(10, 7).ref_time())]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let exist_kitty = Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(from.clone()), Error::<T>::NotOwner);
+			ensure!(Self::is_approved_or_owner(&who, &from, kitty_id), Error::<T>::NotApproved);
+
+			Self::do_transfer(&from, &to, kitty_id, exist_kitty)
+		}
+
+		// 设置kitty的名称及元数据URI, 两者均为空时清除元数据
+		#[pallet::call_index(6)]
+		#[pallet::weight(0)]
+		pub fn set_kitty_metadata(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			name: Vec<u8>,
+			metadata_uri: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+			ensure!(!Leases::<T>::contains_key(kitty_id), Error::<T>::KittyOnLease);
+
+			let bounded_name = BoundedVec::<u8, T::MaxNameLength>::try_from(name.clone())
+				.map_err(|_| Error::<T>::NameTooLong)?;
+			let bounded_uri =
+				BoundedVec::<u8, T::MaxMetadataUriLength>::try_from(metadata_uri.clone())
+					.map_err(|_| Error::<T>::MetadataUriTooLong)?;
+
+			// 退还之前设置元数据时质押的token
+			if let Some(old) = MetadataOf::<T>::take(kitty_id) {
+				T::Currency::unreserve(&old.depositor, old.deposit);
+			}
+
+			if bounded_name.is_empty() && bounded_uri.is_empty() {
+				Self::deposit_event(Event::KittyMetadataCleared(who, kitty_id));
+				return Ok(())
+			}
+
+			// 按字节质押token
+			let bytes = (bounded_name.len() + bounded_uri.len()) as u32;
+			let deposit = T::MetadataDepositPerByte::get().saturating_mul(bytes.into());
+			T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::TokenNotEnough)?;
+
+			MetadataOf::<T>::insert(
+				kitty_id,
+				KittyMetadata {
+					name: bounded_name,
+					metadata_uri: bounded_uri,
+					depositor: who.clone(),
+					deposit,
+				},
+			);
+
+			Self::deposit_event(Event::KittyMetadataSet(who, kitty_id, name, metadata_uri));
+
+			Ok(())
+		}
+
+		// 提交铸造承诺commitment = hash(账户, salt), 同时质押token
+		#[pallet::call_index(7)]
+		#[pallet::weight(0)]
+		pub fn commit_create(origin: OriginFor<T>, commitment: T::Hash) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!PendingMints::<T>::contains_key(&who), Error::<T>::CommitAlreadyPending);
+
+			// 质押token
+			T::Currency::reserve(&who, T::KittyReserve::get())
 				.map_err(|_| Error::<T>::TokenNotEnough)?;
 
-			// 删除原拥有者KittyAll存储项需转移的kitty
-			KittyAll::<T>::try_mutate(&who, |owned| {
-				if let Some(index) = owned.iter().position(|kitty| kitty == &exist_kitty) {
-					owned.swap_remove(index);
-					return Ok(())
-				}
-				Err(())
-			})
-			.map_err(|_| Error::<T>::NotOwner)?;
+			let committed_at = <frame_system::Pallet<T>>::block_number();
+			PendingMints::<T>::insert(&who, MintCommit { commitment, committed_at });
+			Self::request_seed(committed_at.saturating_add(T::RevealDelay::get()));
 
-			// 解押原来已质押的token
-			T::Currency::unreserve(&who, T::KittyReserve::get());
+			Self::deposit_event(Event::KittyCommitted(who, committed_at));
+
+			Ok(())
+		}
 
-			<KittyOwner<T>>::insert(kitty_id, new_owner.clone());
+		// 揭示salt并铸造kitty, dna由提交区块 + RevealDelay固定的随机数决定
+		// 窗口内任意区块揭示得到的dna相同, 无法通过选择揭示区块挑选结果
+		#[pallet::call_index(8)]
+		#[pallet::weight(0)]
+		pub fn reveal_create(origin: OriginFor<T>, salt: [u8; 32]) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			// 追加转移的kitty到新拥有者KittyAll存储项中
-			KittyAll::<T>::try_mutate(&new_owner, |kitty_vec| kitty_vec.try_push(exist_kitty))
-				.map_err(|_| Error::<T>::ExceedMaxKittyOwned)?;
+			let commit = Self::pending_mint(&who).ok_or(Error::<T>::NoPendingCommit)?;
+			ensure!(
+				T::Hashing::hash_of(&(&who, salt)) == commit.commitment,
+				Error::<T>::InvalidReveal
+			);
+
+			// 只能在[提交区块 + RevealDelay, 提交区块 + RevealDelay + RevealWindow]内揭示
+			let now = <frame_system::Pallet<T>>::block_number();
+			let reveal_from = commit.committed_at.saturating_add(T::RevealDelay::get());
+			ensure!(now >= reveal_from, Error::<T>::RevealTooEarly);
+			ensure!(
+				now <= reveal_from.saturating_add(T::RevealWindow::get()),
+				Error::<T>::CommitExpired
+			);
+
+			let block_seed = Self::block_seed(reveal_from).ok_or(Error::<T>::RandomnessNotReady)?;
+			let random_seed = (block_seed, b"kitties/reveal", &who, commit.commitment)
+				.using_encoded(blake2_128);
+
+			let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
+			let kitty = Kitty((random_seed, &who, salt).using_encoded(blake2_128));
+
+			// token已在提交承诺时质押
+			PendingMints::<T>::remove(&who);
+			Self::release_seed(reveal_from);
+			Self::do_mint(&who, kitty_id, kitty.clone())?;
+
+			Self::deposit_event(Event::KittyCreated(who, kitty_id, kitty));
+
+			Ok(())
+		}
+
+		// 清除已过期的铸造承诺, 没收质押的token
+		// 揭示前即可算出dna, 退还质押会让放弃不满意的结果没有代价
+		// 随机数未能固定或kitty总数已达上限时无法揭示, 责任不在用户, 退还质押的token
+		#[pallet::call_index(9)]
+		#[pallet::weight(0)]
+		pub fn cancel_commit(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let commit = Self::pending_mint(&who).ok_or(Error::<T>::NoPendingCommit)?;
+
+			// 未过期的承诺不能取消
+			let reveal_from = commit.committed_at.saturating_add(T::RevealDelay::get());
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() >
+					reveal_from.saturating_add(T::RevealWindow::get()),
+				Error::<T>::CommitNotExpired
+			);
+
+			let reveal_blocked = Self::block_seed(reveal_from).is_none() ||
+				Self::kitty_count() >= T::MaxTotalKitties::get();
+
+			PendingMints::<T>::remove(&who);
+			Self::release_seed(reveal_from);
+			if reveal_blocked {
+				T::Currency::unreserve(&who, T::KittyReserve::get());
+			} else {
+				let _ = T::Currency::slash_reserved(&who, T::KittyReserve::get());
+			}
+
+			Self::deposit_event(Event::KittyCommitCancelled(who));
+
+			Ok(())
+		}
+
+		// owner向borrower发出出借要约, 约定租金和出借区块数
+		#[pallet::call_index(10)]
+		#[pallet::weight(0)]
+		pub fn offer_lease(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			borrower: T::AccountId,
+			fee: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+			ensure!(!Leases::<T>::contains_key(kitty_id), Error::<T>::KittyOnLease);
+			ensure!(!ActiveBattles::<T>::contains_key(kitty_id), Error::<T>::KittyInBattle);
+			ensure!(borrower != who, Error::<T>::NotBorrower);
+			ensure!(
+				!duration.is_zero() && duration <= T::MaxLeaseDuration::get(),
+				Error::<T>::InvalidLeaseDuration
+			);
+
+			LeaseOffers::<T>::insert(
+				kitty_id,
+				LeaseOffer { lender: who.clone(), borrower: borrower.clone(), fee, duration },
+			);
+
+			Self::deposit_event(Event::LeaseOffered(who, borrower, kitty_id, fee, duration));
+
+			Ok(())
+		}
+
+		// lender取消尚未被接受的出借要约
+		#[pallet::call_index(11)]
+		#[pallet::weight(0)]
+		pub fn cancel_lease_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let offer = Self::lease_offer(kitty_id).ok_or(Error::<T>::LeaseOfferNotExist)?;
+			ensure!(offer.lender == who, Error::<T>::NotOwner);
+
+			LeaseOffers::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::LeaseOfferCancelled(who, kitty_id));
+
+			Ok(())
+		}
+
+		// borrower接受出借要约, 支付租金后获得kitty直至到期
+		#[pallet::call_index(12)]
+		#[pallet::weight(0)]
+		pub fn accept_lease(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let offer = Self::lease_offer(kitty_id).ok_or(Error::<T>::LeaseOfferNotExist)?;
+			ensure!(offer.borrower == who, Error::<T>::NotBorrower);
+			ensure!(
+				Self::kitty_owner(kitty_id) == Some(offer.lender.clone()),
+				Error::<T>::NotOwner
+			);
+			ensure!(!ActiveBattles::<T>::contains_key(kitty_id), Error::<T>::KittyInBattle);
+			let kitty = Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			// 登记到期区块, 该区块已满时顺延
+			let end = Self::schedule_lease_expiry(
+				<frame_system::Pallet<T>>::block_number().saturating_add(offer.duration),
+				kitty_id,
+			)?;
 
-			Self::deposit_event(Event::KittyTransferred(who, new_owner.clone(), kitty_id));
+			// 支付租金
+			T::Currency::transfer(&who, &offer.lender, offer.fee, ExistenceRequirement::KeepAlive)?;
+
+			// kitty交给borrower使用, 质押的token仍由lender承担
+			Self::remove_owned_kitty(&offer.lender, &kitty)?;
+			Self::add_owned_kitty(&who, kitty)?;
+			KittyOwner::<T>::insert(kitty_id, &who);
+			OwnedKitties::<T>::remove(&offer.lender, kitty_id);
+			OwnedKitties::<T>::insert(&who, kitty_id, ());
+			KittyApprovals::<T>::remove(kitty_id);
+			// lender提出的交换要约随kitty交出而失效, 退还补贴
+			Self::remove_swap_offer(kitty_id);
+			LentOut::<T>::mutate(&offer.lender, |count| *count = count.saturating_add(1));
+
+			LeaseOffers::<T>::remove(kitty_id);
+			Leases::<T>::insert(
+				kitty_id,
+				Lease { lender: offer.lender.clone(), borrower: who.clone(), fee: offer.fee, end },
+			);
+
+			Self::deposit_event(Event::LeaseStarted(offer.lender, who, kitty_id, end));
+
+			Ok(())
+		}
+
+		// borrower提前归还kitty
+		#[pallet::call_index(13)]
+		#[pallet::weight(0)]
+		pub fn return_lease(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let lease = Self::lease(kitty_id).ok_or(Error::<T>::LeaseNotExist)?;
+			ensure!(lease.borrower == who, Error::<T>::NotBorrower);
+
+			LeaseExpiries::<T>::mutate(lease.end, |expiring| {
+				expiring.retain(|expiring_id| expiring_id != &kitty_id)
+			});
+			Leases::<T>::remove(kitty_id);
+			Self::end_lease(kitty_id, lease);
+
+			Ok(())
+		}
+
+		// 用自己的kitty挑战其他账户的kitty, 同时质押赌注
+		#[pallet::call_index(14)]
+		#[pallet::weight(0)]
+		pub fn challenge(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			opponent_kitty_id: T::KittyIndex,
+			wager: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+			let opponent =
+				Self::kitty_owner(opponent_kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(opponent != who, Error::<T>::BattleWithSelf);
+			Self::ensure_can_battle(kitty_id)?;
+
+			let battle_id = Self::next_battle_id();
+			let next_battle_id = battle_id.checked_add(1).ok_or(Error::<T>::BattleIdOverflow)?;
+
+			// 质押赌注
+			T::Currency::reserve(&who, wager).map_err(|_| Error::<T>::TokenNotEnough)?;
+
+			Battles::<T>::insert(
+				battle_id,
+				Battle {
+					challenger: who.clone(),
+					challenger_kitty: kitty_id,
+					opponent_kitty: opponent_kitty_id,
+					wager,
+					accepted: None,
+				},
+			);
+			ActiveBattles::<T>::insert(kitty_id, battle_id);
+			NextBattleId::<T>::put(next_battle_id);
+
+			Self::deposit_event(Event::BattleChallenged(
+				who,
+				battle_id,
+				kitty_id,
+				opponent_kitty_id,
+				wager,
+			));
+
+			Ok(())
+		}
+
+		// 被挑战kitty的owner接受挑战, 质押同等赌注
+		#[pallet::call_index(15)]
+		#[pallet::weight(0)]
+		pub fn accept_challenge(origin: OriginFor<T>, battle_id: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut battle = Self::battle(battle_id).ok_or(Error::<T>::BattleNotExist)?;
+			ensure!(battle.accepted.is_none(), Error::<T>::BattleAlreadyAccepted);
+			ensure!(
+				Self::kitty_owner(battle.opponent_kitty) == Some(who.clone()),
+				Error::<T>::NotOwner
+			);
+			ensure!(battle.challenger != who, Error::<T>::BattleWithSelf);
+			Self::ensure_can_battle(battle.opponent_kitty)?;
+
+			// 质押赌注
+			T::Currency::reserve(&who, battle.wager).map_err(|_| Error::<T>::TokenNotEnough)?;
+
+			let accepted_at = <frame_system::Pallet<T>>::block_number();
+			battle.accepted = Some((who.clone(), accepted_at));
+			ActiveBattles::<T>::insert(battle.opponent_kitty, battle_id);
+			Self::request_seed(accepted_at.saturating_add(T::BattleDelay::get()));
+			Battles::<T>::insert(battle_id, battle);
+
+			Self::deposit_event(Event::BattleAccepted(who, battle_id));
+
+			Ok(())
+		}
+
+		// 挑战者取消或被挑战kitty的owner拒绝尚未接受的挑战, 解押挑战者的赌注
+		#[pallet::call_index(16)]
+		#[pallet::weight(0)]
+		pub fn cancel_challenge(origin: OriginFor<T>, battle_id: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let battle = Self::battle(battle_id).ok_or(Error::<T>::BattleNotExist)?;
+			ensure!(battle.accepted.is_none(), Error::<T>::BattleAlreadyAccepted);
+			ensure!(
+				battle.challenger == who ||
+					Self::kitty_owner(battle.opponent_kitty) == Some(who.clone()),
+				Error::<T>::NotOwner
+			);
+
+			Battles::<T>::remove(battle_id);
+			ActiveBattles::<T>::remove(battle.challenger_kitty);
+			T::Currency::unreserve(&battle.challenger, battle.wager);
+
+			Self::deposit_event(Event::BattleCancelled(who, battle_id));
+
+			Ok(())
+		}
+
+		// 结算对战, 任何账户都可调用, 胜者获得双方的赌注
+		// 胜负由接受区块 + BattleDelay固定的随机数决定, 与结算时所在的区块无关
+		#[pallet::call_index(17)]
+		#[pallet::weight(0)]
+		pub fn resolve_battle(origin: OriginFor<T>, battle_id: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let battle = Self::battle(battle_id).ok_or(Error::<T>::BattleNotExist)?;
+			let (opponent, accepted_at) =
+				battle.accepted.clone().ok_or(Error::<T>::BattleNotAccepted)?;
+
+			// 只能在接受区块 + BattleDelay之后结算
+			let resolve_from = accepted_at.saturating_add(T::BattleDelay::get());
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() >= resolve_from,
+				Error::<T>::BattleTooEarly
+			);
+
+			let block_seed =
+				Self::block_seed(resolve_from).ok_or(Error::<T>::RandomnessNotReady)?;
+			let random_seed = T::Hashing::hash_of(&(block_seed, b"kitties/battle", battle_id));
+
+			let challenger_kitty =
+				Self::get_kitty(battle.challenger_kitty).map_err(|_| Error::<T>::InvalidKittyId)?;
+			let opponent_kitty =
+				Self::get_kitty(battle.opponent_kitty).map_err(|_| Error::<T>::InvalidKittyId)?;
+
+			let (winner, winner_kitty, loser, loser_kitty) =
+				if Self::battle_outcome(&challenger_kitty, &opponent_kitty, random_seed) {
+					(battle.challenger, battle.challenger_kitty, opponent, battle.opponent_kitty)
+				} else {
+					(opponent, battle.opponent_kitty, battle.challenger, battle.challenger_kitty)
+				};
+
+			// 败者的赌注转给胜者, 胜者的赌注解押
+			// 结算不能失败, 否则双方赌注永久锁定, 败者质押不足时按实际转出的数量结算
+			let remainder =
+				T::Currency::repatriate_reserved(&loser, &winner, battle.wager, BalanceStatus::Free)?;
+			let won = battle.wager.saturating_sub(remainder);
+			T::Currency::unreserve(&winner, battle.wager);
+
+			BattleRecords::<T>::mutate(winner_kitty, |record| {
+				record.wins = record.wins.saturating_add(1)
+			});
+			BattleRecords::<T>::mutate(loser_kitty, |record| {
+				record.losses = record.losses.saturating_add(1)
+			});
+
+			Battles::<T>::remove(battle_id);
+			ActiveBattles::<T>::remove(battle.challenger_kitty);
+			ActiveBattles::<T>::remove(battle.opponent_kitty);
+			Self::release_seed(resolve_from);
+
+			Self::deposit_event(Event::BattleResolved(
+				battle_id,
+				winner,
+				winner_kitty,
+				loser_kitty,
+				won,
+			));
+
+			Ok(())
+		}
+
+		// 批量转移自己的kitty给new_owner, 任一kitty转移失败则全部回滚
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(
+			8 * kitty_ids.len() as u64,
+			7 * kitty_ids.len() as u64,
+		))]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			kitty_ids: Vec<T::KittyIndex>,
+			new_owner: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let count = Self::ensure_batch_size(kitty_ids.len())?;
+
+			for kitty_id in kitty_ids {
+				let exist_kitty =
+					Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+				ensure!(Self::kitty_owner(kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+
+				Self::do_transfer(&who, &new_owner, kitty_id, exist_kitty)?;
+			}
+
+			Self::deposit_event(Event::KittiesBatchTransferred(who, new_owner, count));
+
+			Ok(())
+		}
+
+		// 批量创建count个kitty, 一次性质押所有token
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(
+			1 + 5 * *count as u64,
+			1 + 5 * *count as u64,
+		))]
+		pub fn create_batch(origin: OriginFor<T>, count: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::ensure_batch_size(count as usize)?;
+
+			// 质押token
+			T::Currency::reserve(&who, T::KittyReserve::get().saturating_mul(count.into()))
+				.map_err(|_| Error::<T>::TokenNotEnough)?;
+
+			// 同一交易中random_value相同, 按序号派生每个kitty的dna
+			let random_seed = Self::random_value(&who);
+			for index in 0..count {
+				let kitty_id = Self::get_next_id().map_err(|_| Error::<T>::InvalidKittyId)?;
+				let kitty = Kitty((random_seed, index).using_encoded(blake2_128));
+
+				Self::do_mint(&who, kitty_id, kitty.clone())?;
+
+				Self::deposit_event(Event::KittyCreated(who.clone(), kitty_id, kitty));
+			}
+
+			Self::deposit_event(Event::KittiesBatchCreated(who, count));
+
+			Ok(())
+		}
+
+		// 批量销毁自己的kitty, 任一kitty销毁失败则全部回滚
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(
+			7 * kitty_ids.len() as u64,
+			9 * kitty_ids.len() as u64,
+		))]
+		pub fn burn_batch(origin: OriginFor<T>, kitty_ids: Vec<T::KittyIndex>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let count = Self::ensure_batch_size(kitty_ids.len())?;
+
+			for kitty_id in kitty_ids {
+				Self::do_burn(kitty_id, Some(&who))?;
+			}
+
+			Self::deposit_event(Event::KittiesBatchBurned(who, count));
+
+			Ok(())
+		}
+
+		// 提议用自己的kitty交换target, 可附带补贴top_up, 补贴在要约期间质押
+		#[pallet::call_index(21)]
+		#[pallet::weight(0)]
+		pub fn propose_swap(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			target: SwapTarget<T::KittyIndex>,
+			top_up: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+			ensure!(!Leases::<T>::contains_key(kitty_id), Error::<T>::KittyOnLease);
+			ensure!(!ActiveBattles::<T>::contains_key(kitty_id), Error::<T>::KittyInBattle);
+			ensure!(!SwapOffers::<T>::contains_key(kitty_id), Error::<T>::SwapAlreadyProposed);
+			ensure!(target != SwapTarget::Kitty(kitty_id), Error::<T>::SameKittyId);
+			ensure!(
+				!duration.is_zero() && duration <= T::MaxSwapDuration::get(),
+				Error::<T>::InvalidSwapDuration
+			);
+
+			// 质押补贴
+			T::Currency::reserve(&who, top_up).map_err(|_| Error::<T>::TokenNotEnough)?;
+
+			let expires_at = <frame_system::Pallet<T>>::block_number().saturating_add(duration);
+			SwapOffers::<T>::insert(
+				kitty_id,
+				SwapOffer { proposer: who.clone(), target: target.clone(), top_up, expires_at },
+			);
+
+			Self::deposit_event(Event::SwapProposed(who, kitty_id, target, top_up, expires_at));
+
+			Ok(())
+		}
+
+		// 提议者随时可以取消交换要约, 要约过期后任何账户均可清除, 补贴退还提议者
+		#[pallet::call_index(22)]
+		#[pallet::weight(0)]
+		pub fn cancel_swap(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let offer = Self::swap_offer(kitty_id).ok_or(Error::<T>::SwapOfferNotExist)?;
+			ensure!(
+				offer.proposer == who ||
+					<frame_system::Pallet<T>>::block_number() > offer.expires_at,
+				Error::<T>::SwapOfferNotExpired
+			);
+
+			Self::remove_swap_offer(kitty_id);
+
+			Ok(())
+		}
+
+		// 用自己的counter_kitty_id接受交换要约, 双方kitty及补贴在同一交易中交换
+		#[pallet::call_index(23)]
+		#[pallet::weight(0)]
+		pub fn accept_swap(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			counter_kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let offer = Self::swap_offer(kitty_id).ok_or(Error::<T>::SwapOfferNotExist)?;
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() <= offer.expires_at,
+				Error::<T>::SwapOfferExpired
+			);
+			ensure!(
+				Self::kitty_owner(kitty_id) == Some(offer.proposer.clone()),
+				Error::<T>::NotOwner
+			);
+			ensure!(Self::kitty_owner(counter_kitty_id) == Some(who.clone()), Error::<T>::NotOwner);
+			ensure!(offer.proposer != who, Error::<T>::SwapWithSelf);
+			// 要约提出后kitty可能被锁定, 在转移补贴前检查
+			for id in [kitty_id, counter_kitty_id] {
+				ensure!(!Leases::<T>::contains_key(id), Error::<T>::KittyOnLease);
+				ensure!(!ActiveBattles::<T>::contains_key(id), Error::<T>::KittyInBattle);
+			}
+
+			let kitty = Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+			let counter_kitty =
+				Self::get_kitty(counter_kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+			ensure!(
+				Self::swap_target_matches(&offer.target, counter_kitty_id, &counter_kitty),
+				Error::<T>::SwapTargetMismatch
+			);
+
+			// 先删除要约, 避免转移时将补贴退还提议者
+			// 补贴未能足额转出时交换失败, 整个交易回滚
+			SwapOffers::<T>::remove(kitty_id);
+			let remainder = T::Currency::repatriate_reserved(
+				&offer.proposer,
+				&who,
+				offer.top_up,
+				BalanceStatus::Free,
+			)?;
+			ensure!(remainder.is_zero(), Error::<T>::SwapTopUpUnavailable);
+
+			Self::do_transfer(&offer.proposer, &who, kitty_id, kitty)?;
+			Self::do_transfer(&who, &offer.proposer, counter_kitty_id, counter_kitty)?;
+
+			Self::deposit_event(Event::SwapAccepted(
+				offer.proposer,
+				who,
+				kitty_id,
+				counter_kitty_id,
+				offer.top_up,
+			));
+
+			Ok(())
+		}
+
+		// 取消指定kitty的单独授权, owner或其operator可调用
+		#[pallet::call_index(24)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 1).ref_time())]
+		pub fn cancel_approval(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(who == owner || Self::operator_approval(&owner, &who), Error::<T>::NotOwner);
+			ensure!(KittyApprovals::<T>::contains_key(kitty_id), Error::<T>::NotApproved);
+
+			KittyApprovals::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyApprovalCancelled(owner, kitty_id));
 
 			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		// 查询kitty的dna、owner及元数据
+		pub fn kitty_info(
+			kitty_id: T::KittyIndex,
+		) -> Option<KittyInfo<T::AccountId, T::KittyIndex>> {
+			let kitty = Self::kitties(kitty_id)?;
+			let owner = Self::kitty_owner(kitty_id)?;
+			let (name, metadata_uri) = Self::metadata_of(kitty_id)
+				.map(|metadata| (metadata.name.into_inner(), metadata.metadata_uri.into_inner()))
+				.unwrap_or_default();
+
+			Some(KittyInfo { id: kitty_id, owner, dna: kitty.0, name, metadata_uri })
+		}
+
+		// 分页查询账户拥有的kitty, 按存储顺序返回第page页(从0开始), 每页page_size个
+		// page_size为None时取KITTIES_PAGE_SIZE, 最大为MAX_KITTIES_PAGE_SIZE
+		// 传入start_after时从该kitty之后开始计页, 以上一页最后一个kitty的编号翻页并传page为0,
+		// 只读取一页的存储; 否则需跳过前page页
+		pub fn kitties_owned_by(
+			owner: &T::AccountId,
+			page: u32,
+			page_size: Option<u32>,
+			start_after: Option<T::KittyIndex>,
+		) -> Vec<KittyInfo<T::AccountId, T::KittyIndex>> {
+			let kitty_ids = match start_after {
+				Some(start) => OwnedKitties::<T>::iter_key_prefix_from(
+					owner,
+					OwnedKitties::<T>::hashed_key_for(owner, start),
+				),
+				None => OwnedKitties::<T>::iter_key_prefix(owner),
+			};
+
+			Self::kitties_page(kitty_ids, page, page_size)
+		}
+
+		// 分页查询所有存在的kitty, 翻页方式同kitties_owned_by
+		pub fn kitties_listed(
+			page: u32,
+			page_size: Option<u32>,
+			start_after: Option<T::KittyIndex>,
+		) -> Vec<KittyInfo<T::AccountId, T::KittyIndex>> {
+			let kitty_ids = match start_after {
+				Some(start) => Kitties::<T>::iter_keys_from(Kitties::<T>::hashed_key_for(start)),
+				None => Kitties::<T>::iter_keys(),
+			};
+
+			Self::kitties_page(kitty_ids, page, page_size)
+		}
+
+		fn kitties_page(
+			kitty_ids: impl Iterator<Item = T::KittyIndex>,
+			page: u32,
+			page_size: Option<u32>,
+		) -> Vec<KittyInfo<T::AccountId, T::KittyIndex>> {
+			let page_size =
+				page_size.unwrap_or(KITTIES_PAGE_SIZE).min(MAX_KITTIES_PAGE_SIZE) as usize;
+			kitty_ids
+				.skip((page as usize).saturating_mul(page_size))
+				.take(page_size)
+				.filter_map(Self::kitty_info)
+				.collect()
+		}
+
+		// 对战结果, 挑战者获胜返回true
+		// 双方战力为dna各字节之和加1, 随机数在双方战力之和内落点决定胜负, 战力越高胜率越大
+		pub fn battle_outcome(challenger: &Kitty, opponent: &Kitty, random_seed: T::Hash) -> bool {
+			let power = |kitty: &Kitty| kitty.0.iter().map(|gene| *gene as u32).sum::<u32>() + 1;
+			let challenger_power = power(challenger);
+			let opponent_power = power(opponent);
+
+			let random = (random_seed, b"kitties/battle").using_encoded(blake2_128);
+			let roll = u32::from_le_bytes([random[0], random[1], random[2], random[3]]) %
+				(challenger_power + opponent_power);
+
+			roll < challenger_power
+		}
+
+		// 判断kitty是否满足交换要约期望的条件
+		pub fn swap_target_matches(
+			target: &SwapTarget<T::KittyIndex>,
+			kitty_id: T::KittyIndex,
+			kitty: &Kitty,
+		) -> bool {
+			match target {
+				SwapTarget::Kitty(target_id) => *target_id == kitty_id,
+				SwapTarget::Traits { mask, value, gender } => {
+					let traits_match = (0..16).all(|i| kitty.0[i] & mask[i] == value[i] & mask[i]);
+					traits_match && gender.map_or(true, |gender| kitty.gender() == gender)
+				},
+			}
+		}
+
+		// 登记需要在区块at的on_initialize中固定随机数
+		pub(crate) fn request_seed(at: T::BlockNumber) {
+			SeedRequests::<T>::mutate(at, |count| *count = count.saturating_add(1));
+		}
+
+		// 不再需要区块at的随机数, 最后一个使用者释放后删除
+		fn release_seed(at: T::BlockNumber) {
+			SeedRequests::<T>::mutate_exists(at, |count| {
+				let remaining = count.unwrap_or_default().saturating_sub(1);
+				*count = (remaining > 0).then_some(remaining);
+			});
+			if !SeedRequests::<T>::contains_key(at) {
+				BlockSeeds::<T>::remove(at);
+			}
+		}
+
+		// 删除交换要约并退还补贴
+		fn remove_swap_offer(kitty_id: T::KittyIndex) {
+			if let Some(offer) = SwapOffers::<T>::take(kitty_id) {
+				T::Currency::unreserve(&offer.proposer, offer.top_up);
+				Self::deposit_event(Event::SwapCancelled(offer.proposer, kitty_id));
+			}
+		}
+
+		// 判断两个kitty是否为父子或(同父或同母的)兄弟姐妹关系
+		fn closely_related(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> bool {
+			let parents_1 = Self::kitty_parents(kitty_id_1);
+			let parents_2 = Self::kitty_parents(kitty_id_2);
+			let has_parent = |parents: Option<(T::KittyIndex, T::KittyIndex)>, kitty_id| {
+				parents.map_or(false, |(parent_1, parent_2)| {
+					parent_1 == kitty_id || parent_2 == kitty_id
+				})
+			};
+
+			// 父子关系
+			if has_parent(parents_1, kitty_id_2) || has_parent(parents_2, kitty_id_1) {
+				return true
+			}
+
+			// 兄弟姐妹关系
+			parents_1.map_or(false, |(parent_1, parent_2)| {
+				has_parent(parents_2, parent_1) || has_parent(parents_2, parent_2)
+			})
+		}
+
+		// 批量操作的数量需在1到MaxBatchSize之间
+		fn ensure_batch_size(len: usize) -> Result<u32, DispatchError> {
+			ensure!(len > 0, Error::<T>::EmptyBatch);
+			ensure!(len <= T::MaxBatchSize::get() as usize, Error::<T>::TooManyInBatch);
+			Ok(len as u32)
+		}
+
+		// 参与对战的kitty不能处于出借中或其他对战中
+		fn ensure_can_battle(kitty_id: T::KittyIndex) -> DispatchResult {
+			ensure!(!Leases::<T>::contains_key(kitty_id), Error::<T>::KittyOnLease);
+			ensure!(!ActiveBattles::<T>::contains_key(kitty_id), Error::<T>::KittyInBattle);
+			Ok(())
+		}
+
 		// 取一个随机值
-		fn random_value(sender: &T::AccountId) -> [u8; 16] {
+		pub(crate) fn random_value(sender: &T::AccountId) -> [u8; 16] {
 			let payload = (
 				T::Randomness::random_seed(),
 				&sender,
@@ -240,8 +1511,179 @@ pub mod pallet {
 			Ok(kitty_id)
 		}
 
+		// 判断who是否为kitty的owner、被授权账户或owner的operator
+		fn is_approved_or_owner(
+			who: &T::AccountId,
+			owner: &T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> bool {
+			who == owner ||
+				Self::kitty_approval(kitty_id).as_ref() == Some(who) ||
+				Self::operator_approval(owner, who)
+		}
+
+		// 转移kitty, 调用方需先校验kitty存在以及from为owner
+		pub(crate) fn do_transfer(
+			from: &T::AccountId,
+			to: &T::AccountId,
+			kitty_id: T::KittyIndex,
+			kitty: Kitty,
+		) -> DispatchResult {
+			// 出借中或对战中的kitty不能转移
+			ensure!(!Leases::<T>::contains_key(kitty_id), Error::<T>::KittyOnLease);
+			ensure!(!ActiveBattles::<T>::contains_key(kitty_id), Error::<T>::KittyInBattle);
+
+			// 新Owner质押token
+			T::Currency::reserve(to, T::KittyReserve::get())
+				.map_err(|_| Error::<T>::TokenNotEnough)?;
+
+			// 删除原拥有者KittyAll存储项需转移的kitty
+			Self::remove_owned_kitty(from, &kitty)?;
+
+			// 解押原来已质押的token
+			T::Currency::unreserve(from, T::KittyReserve::get());
+
+			<KittyOwner<T>>::insert(kitty_id, to.clone());
+			OwnedKitties::<T>::remove(from, kitty_id);
+			OwnedKitties::<T>::insert(to, kitty_id, ());
+
+			// 追加转移的kitty到新拥有者KittyAll存储项中
+			Self::add_owned_kitty(to, kitty)?;
+
+			// 转移后清除原有的单独授权、出借要约及交换要约
+			KittyApprovals::<T>::remove(kitty_id);
+			LeaseOffers::<T>::remove(kitty_id);
+			Self::remove_swap_offer(kitty_id);
+
+			Self::deposit_event(Event::KittyTransferred(from.clone(), to.clone(), kitty_id));
+
+			Ok(())
+		}
+
+		// 铸造kitty并记录owner, 调用方需已完成token质押
+		pub(crate) fn do_mint(
+			owner: &T::AccountId,
+			kitty_id: T::KittyIndex,
+			kitty: Kitty,
+		) -> DispatchResult {
+			ensure!(!Kitties::<T>::contains_key(kitty_id), Error::<T>::KittyAlreadyExists);
+			let next_id = kitty_id.checked_add(&One::one()).ok_or(Error::<T>::KittyIdOverflow)?;
+			let count = Self::kitty_count();
+			ensure!(count < T::MaxTotalKitties::get(), Error::<T>::MaxTotalKittiesReached);
+
+			Self::add_owned_kitty(owner, kitty.clone())?;
+			Kitties::<T>::insert(kitty_id, &kitty);
+			KittyOwner::<T>::insert(kitty_id, owner);
+			OwnedKitties::<T>::insert(owner, kitty_id, ());
+			KittyCount::<T>::put(count + 1);
+
+			if kitty_id >= Self::next_kitty_id() {
+				NextKittyId::<T>::set(next_id);
+			}
+
+			Ok(())
+		}
+
+		// 销毁kitty, 解押owner质押的token并退还元数据押金
+		pub(crate) fn do_burn(
+			kitty_id: T::KittyIndex,
+			maybe_check_owner: Option<&T::AccountId>,
+		) -> DispatchResult {
+			let kitty = Self::get_kitty(kitty_id).map_err(|_| Error::<T>::InvalidKittyId)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			if let Some(check_owner) = maybe_check_owner {
+				ensure!(check_owner == &owner, Error::<T>::NotOwner);
+			}
+			// 出借中或对战中的kitty不能销毁
+			ensure!(!Leases::<T>::contains_key(kitty_id), Error::<T>::KittyOnLease);
+			ensure!(!ActiveBattles::<T>::contains_key(kitty_id), Error::<T>::KittyInBattle);
+			// 计数为0时仍存在kitty说明计数漂移, 报错而不是掩盖
+			let count =
+				Self::kitty_count().checked_sub(1).ok_or(Error::<T>::KittyCountUnderflow)?;
+
+			Self::remove_owned_kitty(&owner, &kitty)?;
+			Kitties::<T>::remove(kitty_id);
+			KittyOwner::<T>::remove(kitty_id);
+			OwnedKitties::<T>::remove(&owner, kitty_id);
+			KittyCount::<T>::put(count);
+			KittyApprovals::<T>::remove(kitty_id);
+			LeaseOffers::<T>::remove(kitty_id);
+			Self::remove_swap_offer(kitty_id);
+			BattleRecords::<T>::remove(kitty_id);
+			KittyParents::<T>::remove(kitty_id);
+			if let Some(metadata) = MetadataOf::<T>::take(kitty_id) {
+				T::Currency::unreserve(&metadata.depositor, metadata.deposit);
+			}
+
+			T::Currency::unreserve(&owner, T::KittyReserve::get());
+
+			Self::deposit_event(Event::KittyBurned(owner, kitty_id));
+
+			Ok(())
+		}
+
+		// 归还出借的kitty给lender, 调用方需已删除Leases中的记录
+		fn end_lease(
+			kitty_id: T::KittyIndex,
+			lease: Lease<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+		) {
+			if let Some(kitty) = Self::kitties(kitty_id) {
+				let _ = Self::remove_owned_kitty(&lease.borrower, &kitty);
+				// 出借时已在lender的KittyAll中保留了位置, 归还不会超限
+				LentOut::<T>::mutate(&lease.lender, |count| *count = count.saturating_sub(1));
+				let _ = Self::add_owned_kitty(&lease.lender, kitty);
+				KittyOwner::<T>::insert(kitty_id, &lease.lender);
+				OwnedKitties::<T>::remove(&lease.borrower, kitty_id);
+				OwnedKitties::<T>::insert(&lease.lender, kitty_id, ());
+				KittyApprovals::<T>::remove(kitty_id);
+			}
+
+			Self::deposit_event(Event::LeaseEnded(lease.lender, lease.borrower, kitty_id));
+		}
+
+		// 在不早于end的第一个未满的区块登记到期, 返回实际的到期区块
+		fn schedule_lease_expiry(
+			end: T::BlockNumber,
+			kitty_id: T::KittyIndex,
+		) -> Result<T::BlockNumber, DispatchError> {
+			let mut at = end;
+			for _ in 0..=LEASE_EXPIRY_SPILL_BLOCKS {
+				if LeaseExpiries::<T>::try_mutate(at, |expiring| expiring.try_push(kitty_id))
+					.is_ok()
+				{
+					return Ok(at)
+				}
+				at = at.saturating_add(One::one());
+			}
+			Err(Error::<T>::TooManyLeasesExpiring.into())
+		}
+
+		// 追加kitty到owner的KittyAll存储项中, 出借中的kitty也占用位置
+		fn add_owned_kitty(owner: &T::AccountId, kitty: Kitty) -> DispatchResult {
+			let lent_out = Self::lent_out(owner);
+			KittyAll::<T>::try_mutate(owner, |kitty_vec| {
+				ensure!(
+					(kitty_vec.len() as u32).saturating_add(lent_out) <
+						T::MaxKittyIndexLength::get(),
+					Error::<T>::ExceedMaxKittyOwned
+				);
+				kitty_vec.try_push(kitty).map_err(|_| Error::<T>::ExceedMaxKittyOwned.into())
+			})
+		}
+
+		// 从owner的KittyAll存储项中删除kitty
+		fn remove_owned_kitty(owner: &T::AccountId, kitty: &Kitty) -> DispatchResult {
+			KittyAll::<T>::try_mutate(owner, |owned| {
+				if let Some(index) = owned.iter().position(|owned_kitty| owned_kitty == kitty) {
+					owned.swap_remove(index);
+					return Ok(())
+				}
+				Err(Error::<T>::NotOwner.into())
+			})
+		}
+
 		// 通过kitty id获取kitty
-		fn get_kitty(kitty_id: T::KittyIndex) -> Result<Kitty, ()> {
+		pub(crate) fn get_kitty(kitty_id: T::KittyIndex) -> Result<Kitty, ()> {
 			match Self::kitties(kitty_id) {
 				Some(kitty) => Ok(kitty),
 				None => Err(()),
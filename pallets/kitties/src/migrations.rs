@@ -0,0 +1,98 @@
+// 存储迁移
+
+// v1：写入存储版本, 并回填声明存储版本之前新增的存储
+// KittyCount按Kitties中实际存在的kitty数量回填, OwnedKitties按KittyOwner回填
+// 进行中的铸造承诺、已接受的对战登记揭示或结算使用的随机数, 区块已过去的直接固定
+// 之后的迁移以链上版本为1作为前提
+pub mod v1 {
+	use crate::{
+		Battles, BlockSeeds, Config, Kitties, KittyCount, KittyOwner, OwnedKitties, Pallet,
+		PendingMints, SeedRequests,
+	};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, Randomness},
+	};
+	use sp_runtime::traits::Saturating;
+	use sp_std::{marker::PhantomData, prelude::*};
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			// 已经迁移过则跳过
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let count = Kitties::<T>::iter().count() as u64;
+			KittyCount::<T>::put(count as u32);
+
+			let mut owned = 0u64;
+			for (kitty_id, owner) in KittyOwner::<T>::iter() {
+				OwnedKitties::<T>::insert(owner, kitty_id, ());
+				owned += 1;
+			}
+
+			let mut seeds = Vec::new();
+			seeds.extend(
+				PendingMints::<T>::iter_values()
+					.map(|commit| commit.committed_at.saturating_add(T::RevealDelay::get())),
+			);
+			seeds.extend(Battles::<T>::iter_values().filter_map(|battle| {
+				battle
+					.accepted
+					.map(|(_, accepted_at)| accepted_at.saturating_add(T::BattleDelay::get()))
+			}));
+
+			// 迁移在本区块on_initialize之前执行, 本区块及之后的随机数由on_initialize固定
+			let now = <frame_system::Pallet<T>>::block_number();
+			let requests = seeds.len() as u64;
+			for at in seeds {
+				Pallet::<T>::request_seed(at);
+				if at < now && !BlockSeeds::<T>::contains_key(at) {
+					let (seed, _) = T::Randomness::random(b"kitties/seed");
+					BlockSeeds::<T>::insert(at, seed);
+				}
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get()
+				.reads_writes(1 + count + owned + 3 * requests, 2 + owned + 2 * requests)
+		}
+
+		// 升级前记录实际存在的kitty数量, 旧链上KittyCount可能尚未回填
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() <= 1,
+				"kitties: unexpected storage version"
+			);
+			Ok((Kitties::<T>::iter().count() as u32).encode())
+		}
+
+		// 升级后版本为1, KittyCount与实际kitty数量一致
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let count: u32 = Decode::decode(&mut &state[..])
+				.map_err(|_| "kitties: invalid pre_upgrade state")?;
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "kitties: storage version not 1");
+			ensure!(Kitties::<T>::iter().count() as u32 == count, "kitties: kitties lost");
+			ensure!(KittyCount::<T>::get() == count, "kitties: KittyCount not backfilled");
+			ensure!(
+				KittyOwner::<T>::iter()
+					.all(|(kitty_id, owner)| OwnedKitties::<T>::contains_key(owner, kitty_id)),
+				"kitties: OwnedKitties not backfilled"
+			);
+			ensure!(
+				PendingMints::<T>::iter_values().all(|commit| {
+					SeedRequests::<T>::contains_key(
+						commit.committed_at.saturating_add(T::RevealDelay::get()),
+					)
+				}),
+				"kitties: reveal seed not requested"
+			);
+			Ok(())
+		}
+	}
+}
@@ -22,7 +22,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system,
 		// 更新此处
-		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Event<T>},
+		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Config<T>, Event<T>},
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip,
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
@@ -83,10 +83,26 @@ impl pallet_kitties::Config for Test {
 	type KittyReserve = KittyReserve;
 	type Randomness = RandomnessCollectiveFlip;
 	type Currency = Balances;
+	type MaxNameLength = ConstU32<32>;
+	type MaxMetadataUriLength = ConstU32<128>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type RevealDelay = ConstU64<82>;
+	type RevealWindow = ConstU64<10>;
+	type MaxLeaseDuration = ConstU64<100>;
+	type MaxLeasesPerBlock = ConstU32<2>;
+	type MaxTotalKitties = ConstU32<1000>;
+	type BattleDelay = ConstU64<82>;
+	type MaxBatchSize = ConstU32<5>;
+	type MaxSwapDuration = ConstU64<100>;
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_kitties(vec![])
+}
+
+// 创世时创建指定的kitty: (owner, dna)
+pub fn new_test_ext_with_kitties(kitties: Vec<(u64, [u8; 16])>) -> sp_io::TestExternalities {
 	let mut storage =
 		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
 
@@ -95,6 +111,10 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		.assimilate_storage(&mut storage)
 		.unwrap();
 
+	pallet_kitties::GenesisConfig::<Test> { kitties }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
 	let mut ext: sp_io::TestExternalities = storage.into();
 	ext.execute_with(|| System::set_block_number(1));
 	ext
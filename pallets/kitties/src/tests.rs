@@ -1,6 +1,19 @@
 use super::*;
 use crate::mock::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::TransactionOutcome,
+	traits::{
+		tokens::{nonfungible, nonfungibles},
+		Hooks, Randomness,
+	},
+};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+// dna首字节为偶数的kitty为公, 奇数为母
+const MALE_DNA: [u8; 16] = [0u8; 16];
+const FEMALE_DNA: [u8; 16] = [1u8; 16];
 
 /// 创建Kitty成功, 且质押成功、创建Kitty后存储该账号拥有的此Kitty成功
 #[test]
@@ -46,13 +59,8 @@ fn create_kitty_and_reversed_failed() {
 /// 繁殖Kitty成功
 #[test]
 fn breed_kitty_works() {
-	new_test_ext().execute_with(|| {
-		// 用账户1创建一个kitty,kitty编号为0
-		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
-
-		// 用账户1再创建一个kitty,kitty编号经过累加变为1
-		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
-
+	// 账户1拥有一公(kitty 0)一母(kitty 1)两个kitty
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (1, FEMALE_DNA)]).execute_with(|| {
 		// 繁殖一个新kitty, 对应账户1
 		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1));
 
@@ -61,6 +69,9 @@ fn breed_kitty_works() {
 
 		// 经过繁殖，预期账户1下有3个kitty
 		assert_eq!(KittyAll::<Test>::take(1).len(), 3);
+
+		// 记录新kitty的父母
+		assert_eq!(KittyParents::<Test>::get(2), Some((0, 1)));
 	})
 }
 
@@ -97,13 +108,8 @@ fn breed_kitty_failed_invalid_parent_kitty_id() {
 /// 繁殖Kitty失败 - KittyId数超限无法再获取
 #[test]
 fn breed_kitty_failed_get_new_kitty_id() {
-	new_test_ext().execute_with(|| {
-		// 用账户1创建一个kitty,kitty编号为0
-		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
-
-		// 用账户2创建一个kitty,kitty编号经过累加变为1
-		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(2)));
-
+	// 账户1拥有一公(kitty 0)一母(kitty 1)两个kitty
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (1, FEMALE_DNA)]).execute_with(|| {
 		// 填充kittyId的最大值
 		NextKittyId::<Test>::put(u32::MAX);
 
@@ -115,6 +121,22 @@ fn breed_kitty_failed_get_new_kitty_id() {
 	})
 }
 
+/// 繁殖Kitty失败 - 只能用自己拥有或租用中的kitty繁殖
+#[test]
+fn breed_kitty_failed_not_owner() {
+	// 账户1拥有kitty 0(公), 账户2拥有kitty 1(母)
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA)]).execute_with(|| {
+		assert_noop!(KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1), Error::<Test>::NotOwner);
+
+		// 账户2把kitty 1出借给账户1, 出借期间账户1可以繁殖, 账户2不能
+		assert_ok!(KittiesModule::offer_lease(RuntimeOrigin::signed(2), 1, 1, 0, 10));
+		assert_ok!(KittiesModule::accept_lease(RuntimeOrigin::signed(1), 1));
+		assert_noop!(KittiesModule::breed(RuntimeOrigin::signed(2), 1, 0), Error::<Test>::NotOwner);
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1));
+		assert_eq!(KittyOwner::<Test>::get(2), Some(1));
+	})
+}
+
 /// 繁殖Kitty时质押token失败 - 余额数太小不足以质押
 #[test]
 fn breed_kitty_failed_token_not_enough_to_reversed() {
@@ -207,3 +229,1247 @@ fn transfer_kitty_failed_token_not_enough_to_reversed() {
 		);
 	})
 }
+
+/// 授权后被授权账户代owner转移Kitty成功，且转移后授权被清除
+#[test]
+fn approve_and_transfer_from_works() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+
+		// 账户1授权账户2转移kitty编号为0的kitty
+		assert_ok!(KittiesModule::approve(RuntimeOrigin::signed(1), 0, 2));
+		assert_eq!(KittyApprovals::<Test>::get(0), Some(2));
+
+		// 账户2代账户1把kitty转移给自己，预期成功
+		assert_ok!(KittiesModule::transfer_from(RuntimeOrigin::signed(2), 1, 2, 0));
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(2));
+
+		// 转移后授权被清除
+		assert_eq!(KittyApprovals::<Test>::get(0), None);
+	})
+}
+
+/// operator代owner转移Kitty成功，取消授权后转移失败
+#[test]
+fn set_approval_for_all_works() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建两个kitty,kitty编号为0和1
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+
+		// 账户1授权账户3为operator
+		assert_ok!(KittiesModule::set_approval_for_all(RuntimeOrigin::signed(1), 3, true));
+		assert!(OperatorApprovals::<Test>::get(1, 3));
+
+		// 账户3代账户1把kitty编号为0的kitty转移给账户2，预期成功
+		assert_ok!(KittiesModule::transfer_from(RuntimeOrigin::signed(3), 1, 2, 0));
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(2));
+
+		// 取消授权后，账户3无法再转移账户1的kitty
+		assert_ok!(KittiesModule::set_approval_for_all(RuntimeOrigin::signed(1), 3, false));
+		assert_noop!(
+			KittiesModule::transfer_from(RuntimeOrigin::signed(3), 1, 2, 1),
+			Error::<Test>::NotApproved
+		);
+	})
+}
+
+/// 代转移Kitty失败 - 未被授权
+#[test]
+fn transfer_from_failed_not_approved() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+
+		// 账户2未被授权，预期将报错
+		assert_noop!(
+			KittiesModule::transfer_from(RuntimeOrigin::signed(2), 1, 2, 0),
+			Error::<Test>::NotApproved
+		);
+	})
+}
+
+/// 取消单独授权后被授权账户无法代owner转移Kitty
+#[test]
+fn cancel_approval_works() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0, 并授权账户2
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		assert_ok!(KittiesModule::approve(RuntimeOrigin::signed(1), 0, 2));
+
+		// 非owner且非operator不能取消授权
+		assert_noop!(
+			KittiesModule::cancel_approval(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::NotOwner
+		);
+
+		assert_ok!(KittiesModule::cancel_approval(RuntimeOrigin::signed(1), 0));
+		assert_eq!(KittyApprovals::<Test>::get(0), None);
+		System::assert_last_event(Event::KittyApprovalCancelled(1, 0).into());
+
+		// 取消后账户2无法再转移, 且不能重复取消
+		assert_noop!(
+			KittiesModule::transfer_from(RuntimeOrigin::signed(2), 1, 2, 0),
+			Error::<Test>::NotApproved
+		);
+		assert_noop!(
+			KittiesModule::cancel_approval(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::NotApproved
+		);
+	})
+}
+
+/// 授权失败 - 非owner授权或授权给owner自己
+#[test]
+fn approve_failed() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+
+		// 账户2不是owner，预期将报错
+		assert_noop!(
+			KittiesModule::approve(RuntimeOrigin::signed(2), 0, 3),
+			Error::<Test>::NotOwner
+		);
+
+		// 授权给owner自己，预期将报错
+		assert_noop!(
+			KittiesModule::approve(RuntimeOrigin::signed(1), 0, 1),
+			Error::<Test>::ApproveToSelf
+		);
+	})
+}
+
+/// 设置Kitty元数据成功，按字节质押token，清除元数据后退还质押
+#[test]
+fn set_kitty_metadata_works() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		let reserved = Balances::reserved_balance(1);
+
+		// 设置名称和元数据URI，共10个字节，每字节质押1
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			RuntimeOrigin::signed(1),
+			0,
+			b"tom".to_vec(),
+			b"ipfs://".to_vec(),
+		));
+		assert_eq!(Balances::reserved_balance(1), reserved + 10);
+
+		// 查询接口中包含元数据
+		let info = KittiesModule::kitty_info(0).unwrap();
+		assert_eq!(info.owner, 1);
+		assert_eq!(info.name, b"tom".to_vec());
+		assert_eq!(info.metadata_uri, b"ipfs://".to_vec());
+
+		// 名称和URI都为空时清除元数据，并退还质押
+		assert_ok!(KittiesModule::set_kitty_metadata(RuntimeOrigin::signed(1), 0, vec![], vec![]));
+		assert_eq!(MetadataOf::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(1), reserved);
+	})
+}
+
+/// 设置Kitty元数据失败 - 非owner或名称过长
+#[test]
+fn set_kitty_metadata_failed() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+
+		// 账户2不是owner，预期将报错
+		assert_noop!(
+			KittiesModule::set_kitty_metadata(RuntimeOrigin::signed(2), 0, b"tom".to_vec(), vec![]),
+			Error::<Test>::NotOwner
+		);
+
+		// 名称超过最大长度32，预期将报错
+		assert_noop!(
+			KittiesModule::set_kitty_metadata(RuntimeOrigin::signed(1), 0, vec![1; 33], vec![]),
+			Error::<Test>::NameTooLong
+		);
+	})
+}
+
+/// 通过nonfungible接口查询、转移Kitty
+#[test]
+fn nonfungible_inspect_and_transfer_works() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		assert_ok!(KittiesModule::set_kitty_metadata(
+			RuntimeOrigin::signed(1),
+			0,
+			b"tom".to_vec(),
+			vec![]
+		));
+
+		assert_eq!(<KittiesModule as nonfungible::Inspect<u64>>::owner(&0), Some(1));
+		assert_eq!(
+			<KittiesModule as nonfungible::Inspect<u64>>::attribute(&0, b"dna"),
+			Some(KittiesModule::kitties(0).unwrap().0.to_vec())
+		);
+		assert_eq!(
+			<KittiesModule as nonfungible::Inspect<u64>>::attribute(&0, b"name"),
+			Some(b"tom".to_vec())
+		);
+		assert!(!<KittiesModule as nonfungible::Inspect<u64>>::can_transfer(&1));
+
+		// 转移给账户2
+		assert_ok!(<KittiesModule as nonfungible::Transfer<u64>>::transfer(&0, &2));
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(2));
+	})
+}
+
+/// 通过nonfungibles接口铸造、销毁Kitty
+#[test]
+fn nonfungibles_mint_and_burn_works() {
+	new_test_ext().execute_with(|| {
+		// 以指定的kitty编号5铸造kitty给账户1
+		assert_ok!(<KittiesModule as nonfungibles::Mutate<u64>>::mint_into(&(), &5, &1));
+		assert_eq!(<KittiesModule as nonfungibles::Inspect<u64>>::owner(&(), &5), Some(1));
+		assert_eq!(Balances::reserved_balance(1), 1000);
+		assert_eq!(NextKittyId::<Test>::get(), 6);
+
+		// 编号已存在，预期将报错
+		assert_noop!(
+			<KittiesModule as nonfungibles::Mutate<u64>>::mint_into(&(), &5, &2),
+			Error::<Test>::KittyAlreadyExists
+		);
+
+		// 账户2不是owner，销毁失败
+		assert_noop!(
+			<KittiesModule as nonfungibles::Mutate<u64>>::burn(&(), &5, Some(&2)),
+			Error::<Test>::NotOwner
+		);
+
+		// 销毁kitty并解押token
+		assert_ok!(<KittiesModule as nonfungibles::Mutate<u64>>::burn(&(), &5, Some(&1)));
+		assert_eq!(Kitties::<Test>::get(5), None);
+		assert_eq!(KittyAll::<Test>::get(1).len(), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	})
+}
+
+/// 提交-揭示方式创建Kitty成功
+#[test]
+fn commit_and_reveal_create_works() {
+	new_test_ext().execute_with(|| {
+		let salt = [7u8; 32];
+		let commitment = BlakeTwo256::hash_of(&(1u64, salt));
+
+		// 账户1在区块1提交承诺，并质押token
+		assert_ok!(KittiesModule::commit_create(RuntimeOrigin::signed(1), commitment));
+		assert_eq!(Balances::reserved_balance(1), 1000);
+
+		// 同一账户不能同时提交两个承诺
+		assert_noop!(
+			KittiesModule::commit_create(RuntimeOrigin::signed(1), commitment),
+			Error::<Test>::CommitAlreadyPending
+		);
+
+		// 未到揭示区块，预期将报错
+		assert_noop!(
+			KittiesModule::reveal_create(RuntimeOrigin::signed(1), salt),
+			Error::<Test>::RevealTooEarly
+		);
+
+		// 区块1 + RevealDelay(82)的随机数在on_initialize中固定之前不能揭示
+		System::set_block_number(83);
+		assert_eq!(SeedRequests::<Test>::get(83), 1);
+		assert_noop!(
+			KittiesModule::reveal_create(RuntimeOrigin::signed(1), salt),
+			Error::<Test>::RandomnessNotReady
+		);
+
+		// 随机数固定后揭示成功，kitty编号为0
+		KittiesModule::on_initialize(83);
+		assert!(BlockSeeds::<Test>::contains_key(83));
+		assert_noop!(
+			KittiesModule::reveal_create(RuntimeOrigin::signed(1), [8u8; 32]),
+			Error::<Test>::InvalidReveal
+		);
+		assert_ok!(KittiesModule::reveal_create(RuntimeOrigin::signed(1), salt));
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(1));
+		assert_eq!(PendingMints::<Test>::get(1), None);
+
+		// 不再需要的随机数被清除
+		assert_eq!(SeedRequests::<Test>::get(83), 0);
+		assert_eq!(BlockSeeds::<Test>::get(83), None);
+
+		// 揭示时不再额外质押token
+		assert_eq!(Balances::reserved_balance(1), 1000);
+	})
+}
+
+/// 在揭示窗口内的不同区块揭示，得到的dna相同
+#[test]
+fn reveal_at_any_block_in_window_gives_same_dna() {
+	new_test_ext().execute_with(|| {
+		let salt = [7u8; 32];
+		let commitment = BlakeTwo256::hash_of(&(1u64, salt));
+		assert_ok!(KittiesModule::commit_create(RuntimeOrigin::signed(1), commitment));
+
+		System::set_block_number(83);
+		KittiesModule::on_initialize(83);
+
+		// 在区块83揭示得到的dna, 之后回滚
+		let reveal = || {
+			frame_support::storage::with_transaction(|| {
+				let result = KittiesModule::reveal_create(RuntimeOrigin::signed(1), salt)
+					.map(|_| Kitties::<Test>::get(0).expect("kitty minted").0);
+				TransactionOutcome::Rollback(result)
+			})
+		};
+		let dna_at_83 = reveal().expect("reveal works");
+
+		// 之后的区块产生了新的随机数, 在窗口末尾揭示仍得到相同的dna
+		for n in 84..=93u64 {
+			System::set_block_number(n);
+			System::set_parent_hash(H256::repeat_byte(n as u8));
+			RandomnessCollectiveFlip::on_initialize(n);
+		}
+		assert_ne!(
+			RandomnessCollectiveFlip::random(b"kitties/seed").0,
+			BlockSeeds::<Test>::get(83).unwrap()
+		);
+		assert_eq!(reveal(), Ok(dna_at_83));
+	})
+}
+
+/// 铸造承诺过期后揭示失败，取消承诺并没收质押的token
+#[test]
+fn commit_expired_and_cancel_works() {
+	new_test_ext().execute_with(|| {
+		let salt = [7u8; 32];
+		let commitment = BlakeTwo256::hash_of(&(1u64, salt));
+		assert_ok!(KittiesModule::commit_create(RuntimeOrigin::signed(1), commitment));
+
+		// 承诺未过期时不能取消
+		assert_noop!(
+			KittiesModule::cancel_commit(RuntimeOrigin::signed(1)),
+			Error::<Test>::CommitNotExpired
+		);
+
+		// 超过RevealDelay + RevealWindow后承诺过期
+		KittiesModule::on_initialize(83);
+		System::set_block_number(94);
+		assert_noop!(
+			KittiesModule::reveal_create(RuntimeOrigin::signed(1), salt),
+			Error::<Test>::CommitExpired
+		);
+
+		assert_ok!(KittiesModule::cancel_commit(RuntimeOrigin::signed(1)));
+		assert_eq!(PendingMints::<Test>::get(1), None);
+		assert_eq!(SeedRequests::<Test>::get(83), 0);
+		assert_eq!(BlockSeeds::<Test>::get(83), None);
+
+		// 质押的token被没收而不是退还
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 10001 - 1000);
+	})
+}
+
+/// kitty总数达到上限导致无法揭示时, 取消过期承诺退还质押的token
+#[test]
+fn cancel_commit_refunds_when_reveal_blocked() {
+	new_test_ext().execute_with(|| {
+		let salt = [7u8; 32];
+		let commitment = BlakeTwo256::hash_of(&(1u64, salt));
+		assert_ok!(KittiesModule::commit_create(RuntimeOrigin::signed(1), commitment));
+
+		System::set_block_number(83);
+		KittiesModule::on_initialize(83);
+		KittyCount::<Test>::put(1000);
+		assert_noop!(
+			KittiesModule::reveal_create(RuntimeOrigin::signed(1), salt),
+			Error::<Test>::MaxTotalKittiesReached
+		);
+
+		System::set_block_number(94);
+		assert_ok!(KittiesModule::cancel_commit(RuntimeOrigin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 10001);
+	})
+}
+
+/// 出借Kitty成功，到期后在on_initialize中自动归还
+#[test]
+fn lease_kitty_works() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+
+		// 账户1以租金100出借给账户2，出借10个区块
+		assert_ok!(KittiesModule::offer_lease(RuntimeOrigin::signed(1), 0, 2, 100, 10));
+		assert_ok!(KittiesModule::accept_lease(RuntimeOrigin::signed(2), 0));
+
+		// 租金已支付，kitty归账户2使用，到期区块为11
+		assert_eq!(Balances::free_balance(1), 10001 - 1000 + 100);
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(2));
+		assert_eq!(KittyAll::<Test>::get(2).len(), 1);
+		assert_eq!(Leases::<Test>::get(0).unwrap().end, 11);
+		assert_eq!(LentOut::<Test>::get(1), 1);
+
+		// 出借中的kitty不能被borrower转移或销毁
+		assert_noop!(
+			KittiesModule::transfer(RuntimeOrigin::signed(2), 0, 3),
+			Error::<Test>::KittyOnLease
+		);
+		assert_noop!(
+			<KittiesModule as nonfungible::Mutate<u64>>::burn(&0, Some(&2)),
+			Error::<Test>::KittyOnLease
+		);
+
+		// 到期后自动归还账户1
+		KittiesModule::on_initialize(11);
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(1));
+		assert_eq!(KittyAll::<Test>::get(1).len(), 1);
+		assert_eq!(KittyAll::<Test>::get(2).len(), 0);
+		assert_eq!(Leases::<Test>::get(0), None);
+		assert_eq!(LentOut::<Test>::get(1), 0);
+	})
+}
+
+/// 出借Kitty时lender提出的交换要约失效并退还补贴
+#[test]
+fn accept_lease_clears_swap_offer() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA)]).execute_with(|| {
+		assert_ok!(KittiesModule::propose_swap(
+			RuntimeOrigin::signed(1),
+			0,
+			SwapTarget::Kitty(1),
+			100,
+			10
+		));
+		assert_ok!(KittiesModule::offer_lease(RuntimeOrigin::signed(1), 0, 3, 0, 10));
+		assert_ok!(KittiesModule::accept_lease(RuntimeOrigin::signed(3), 0));
+
+		assert_eq!(SwapOffers::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(1), 1000);
+		System::assert_has_event(Event::SwapCancelled(1, 0).into());
+		assert_noop!(
+			KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1),
+			Error::<Test>::SwapOfferNotExist
+		);
+	})
+}
+
+/// borrower提前归还Kitty
+#[test]
+fn return_lease_works() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0，并出借给账户2
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		assert_ok!(KittiesModule::offer_lease(RuntimeOrigin::signed(1), 0, 2, 0, 10));
+		assert_ok!(KittiesModule::accept_lease(RuntimeOrigin::signed(2), 0));
+
+		// 非borrower不能归还
+		assert_noop!(
+			KittiesModule::return_lease(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::NotBorrower
+		);
+
+		assert_ok!(KittiesModule::return_lease(RuntimeOrigin::signed(2), 0));
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(1));
+		assert_eq!(LeaseExpiries::<Test>::get(11).len(), 0);
+	})
+}
+
+/// 出借Kitty失败 - 出借区块数无效
+#[test]
+fn lease_kitty_failed() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建一个kitty,kitty编号为0
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+
+		// 出借区块数超过MaxLeaseDuration(100)，预期将报错
+		assert_noop!(
+			KittiesModule::offer_lease(RuntimeOrigin::signed(1), 0, 2, 0, 101),
+			Error::<Test>::InvalidLeaseDuration
+		);
+	})
+}
+
+/// 同一区块最多到期MaxLeasesPerBlock(2)个出借, 超出的顺延到下一区块到期
+#[test]
+fn lease_expiry_spills_into_next_block() {
+	new_test_ext().execute_with(|| {
+		// 用账户1创建三个kitty,kitty编号为0、1、2, 均出借给账户2
+		for kitty_id in 0..3 {
+			assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+			assert_ok!(KittiesModule::offer_lease(RuntimeOrigin::signed(1), kitty_id, 2, 0, 10));
+			assert_ok!(KittiesModule::accept_lease(RuntimeOrigin::signed(2), kitty_id));
+		}
+		assert_eq!(LeaseExpiries::<Test>::get(11).into_inner(), vec![0, 1]);
+		assert_eq!(LeaseExpiries::<Test>::get(12).into_inner(), vec![2]);
+		assert_eq!(Leases::<Test>::get(2).unwrap().end, 12);
+		System::assert_last_event(Event::LeaseStarted(1, 2, 2, 12).into());
+
+		// 区块11归还前两个, 区块12归还顺延的kitty
+		KittiesModule::on_initialize(11);
+		assert_eq!(KittyOwner::<Test>::try_get(1), Ok(1));
+		assert_eq!(KittyOwner::<Test>::try_get(2), Ok(2));
+		KittiesModule::on_initialize(12);
+		assert_eq!(KittyOwner::<Test>::try_get(2), Ok(1));
+		assert_eq!(Leases::<Test>::get(2), None);
+	})
+}
+
+/// 创世时创建kitty成功
+#[test]
+fn genesis_kitties_works() {
+	new_test_ext_with_kitties(vec![(1, [0u8; 16]), (2, [1u8; 16])]).execute_with(|| {
+		assert_eq!(NextKittyId::<Test>::get(), 2);
+		assert_eq!(KittyCount::<Test>::get(), 2);
+		assert_eq!(KittyOwner::<Test>::try_get(1), Ok(2));
+		assert_eq!(Kitties::<Test>::get(1), Some(Kitty([1u8; 16])));
+
+		// 创世kitty同样质押了token
+		assert_eq!(Balances::reserved_balance(1), 1000);
+	})
+}
+
+/// 创建及繁殖Kitty失败 - 超过kitty总数上限
+#[test]
+fn create_and_breed_failed_max_total_kitties() {
+	// 账户1拥有一公(kitty 0)一母(kitty 1)两个kitty
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (1, FEMALE_DNA)]).execute_with(|| {
+		// 填充kitty总数至上限MaxTotalKitties(1000)
+		KittyCount::<Test>::put(1000);
+
+		assert_noop!(
+			KittiesModule::create(RuntimeOrigin::signed(1)),
+			Error::<Test>::MaxTotalKittiesReached
+		);
+		assert_noop!(
+			KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1),
+			Error::<Test>::MaxTotalKittiesReached
+		);
+	})
+}
+
+/// KittyCount与实际kitty数量不一致时销毁失败
+#[test]
+fn burn_failed_kitty_count_underflow() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA)]).execute_with(|| {
+		// 模拟未回填计数的链
+		KittyCount::<Test>::kill();
+
+		assert_noop!(KittiesModule::do_burn(0, Some(&1)), Error::<Test>::KittyCountUnderflow);
+		assert_eq!(KittyOwner::<Test>::get(0), Some(1));
+	})
+}
+
+/// 分页查询kitty, 按页码或以上一页最后一个kitty的编号翻页, 可指定每页数量
+#[test]
+fn kitties_pagination_works() {
+	new_test_ext().execute_with(|| {
+		// 直接写入25个kitty, 偶数编号归账户1, 奇数编号归账户2, 并删除编号3的kitty
+		for kitty_id in 0..25u32 {
+			let owner = if kitty_id % 2 == 0 { 1 } else { 2 };
+			Kitties::<Test>::insert(kitty_id, Kitty([kitty_id as u8; 16]));
+			KittyOwner::<Test>::insert(kitty_id, owner);
+			OwnedKitties::<Test>::insert(owner, kitty_id, ());
+		}
+		Kitties::<Test>::remove(3);
+		KittyOwner::<Test>::remove(3);
+		OwnedKitties::<Test>::remove(2, 3);
+
+		let ids = |kitties: Vec<KittyInfo<u64, u32>>| {
+			kitties.into_iter().map(|kitty| kitty.id).collect::<Vec<_>>()
+		};
+		// 从首页开始逐页读取, 直到返回空页
+		let pages = |query: &dyn Fn(Option<u32>) -> Vec<KittyInfo<u64, u32>>| {
+			let mut pages = Vec::new();
+			let mut start_after = None;
+			loop {
+				let page = ids(query(start_after));
+				if page.is_empty() {
+					return pages
+				}
+				start_after = page.last().copied();
+				pages.push(page);
+			}
+		};
+		let sorted = |mut kitty_ids: Vec<u32>| {
+			kitty_ids.sort();
+			kitty_ids
+		};
+
+		// 每页最多KITTIES_PAGE_SIZE个, 不重复且跳过已删除的编号3
+		let listed = pages(&|start_after| KittiesModule::kitties_listed(0, None, start_after));
+		assert_eq!(listed[0].len(), KITTIES_PAGE_SIZE as usize);
+		assert_eq!(listed[1].len(), 4);
+		assert_eq!(sorted(listed.concat()), (0..25).filter(|id| *id != 3).collect::<Vec<_>>());
+
+		// 按账户查询只读取该账户的kitty
+		let owned_by_1 =
+			pages(&|start_after| KittiesModule::kitties_owned_by(&1, 0, None, start_after));
+		assert_eq!(sorted(owned_by_1.concat()), (0..25).step_by(2).collect::<Vec<_>>());
+		let owned_by_2 =
+			pages(&|start_after| KittiesModule::kitties_owned_by(&2, 0, None, start_after));
+		assert_eq!(sorted(owned_by_2.concat()), vec![1, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23]);
+		assert!(KittiesModule::kitties_owned_by(&3, 0, None, None).is_empty());
+
+		// 按页码翻页与按编号翻页结果相同, 超出范围的页为空
+		assert_eq!(ids(KittiesModule::kitties_listed(1, None, None)), listed[1]);
+		assert!(KittiesModule::kitties_listed(2, None, None).is_empty());
+		assert_eq!(
+			ids(KittiesModule::kitties_owned_by(&2, 1, Some(5), None)),
+			owned_by_2[0][5..10]
+		);
+
+		// 调用方指定每页数量, 页码从start_after之后开始计算, 每页数量不超过MAX_KITTIES_PAGE_SIZE
+		let all = listed.concat();
+		assert_eq!(ids(KittiesModule::kitties_listed(1, Some(3), None)), all[3..6]);
+		assert_eq!(ids(KittiesModule::kitties_listed(1, Some(3), Some(all[2]))), all[6..9]);
+		assert_eq!(KittiesModule::kitties_listed(0, Some(u32::MAX), None).len(), all.len());
+		assert!(KittiesModule::kitties_listed(0, Some(0), None).is_empty());
+
+		// 作为翻页起点的kitty被删除后, 仍从原位置继续
+		let last = *listed[0].last().unwrap();
+		Kitties::<Test>::remove(last);
+		assert_eq!(ids(KittiesModule::kitties_listed(0, None, Some(last))), listed[1]);
+
+		let kitty = KittiesModule::kitty_info(4).unwrap();
+		assert_eq!((kitty.owner, kitty.dna), (1, [4u8; 16]));
+	})
+}
+
+/// 转移、出借、归还及销毁kitty时更新按账户索引的kitty编号
+#[test]
+fn owned_kitties_index_follows_owner() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA)]).execute_with(|| {
+		let owned = |who: u64| {
+			let mut kitty_ids = OwnedKitties::<Test>::iter_key_prefix(who).collect::<Vec<_>>();
+			kitty_ids.sort();
+			kitty_ids
+		};
+		assert_eq!((owned(1), owned(2)), (vec![0], vec![1]));
+
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(1), 0, 2));
+		assert_eq!((owned(1), owned(2)), (vec![], vec![0, 1]));
+
+		// 出借期间记在borrower名下, 到期后归还lender
+		assert_ok!(KittiesModule::offer_lease(RuntimeOrigin::signed(2), 1, 1, 0, 10));
+		assert_ok!(KittiesModule::accept_lease(RuntimeOrigin::signed(1), 1));
+		assert_eq!((owned(1), owned(2)), (vec![1], vec![0]));
+		KittiesModule::on_initialize(11);
+		assert_eq!((owned(1), owned(2)), (vec![], vec![0, 1]));
+
+		assert_ok!(KittiesModule::do_burn(0, Some(&2)));
+		assert_eq!(owned(2), vec![1]);
+	})
+}
+
+/// 对战流程: 挑战、接受、结算, 胜者获得赌注并记录战绩
+#[test]
+fn battle_works() {
+	new_test_ext_with_kitties(vec![(1, [200u8; 16]), (2, [3u8; 16])]).execute_with(|| {
+		// 账户1用kitty 0挑战账户2的kitty 1, 赌注500
+		assert_ok!(KittiesModule::challenge(RuntimeOrigin::signed(1), 0, 1, 500));
+		assert_eq!(Balances::reserved_balance(1), 1500);
+		assert_eq!(ActiveBattles::<Test>::get(0), Some(0));
+
+		// 挑战者不能接受自己发起的挑战, 未接受的对战不能结算
+		assert_noop!(
+			KittiesModule::accept_challenge(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::resolve_battle(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::BattleNotAccepted
+		);
+
+		// 账户2在区块1接受挑战
+		assert_ok!(KittiesModule::accept_challenge(RuntimeOrigin::signed(2), 0));
+		assert_eq!(Balances::reserved_balance(2), 1500);
+		assert_eq!(ActiveBattles::<Test>::get(1), Some(0));
+
+		// 接受后不能再取消, 未到结算区块不能结算
+		assert_noop!(
+			KittiesModule::cancel_challenge(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::BattleAlreadyAccepted
+		);
+		assert_noop!(
+			KittiesModule::resolve_battle(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::BattleTooEarly
+		);
+
+		// 区块1 + BattleDelay(82)的随机数固定之后任何账户均可结算
+		System::set_block_number(83);
+		assert_noop!(
+			KittiesModule::resolve_battle(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::RandomnessNotReady
+		);
+		KittiesModule::on_initialize(83);
+		let random_seed =
+			BlakeTwo256::hash_of(&(BlockSeeds::<Test>::get(83).unwrap(), b"kitties/battle", 0u32));
+		let challenger_wins =
+			KittiesModule::battle_outcome(&Kitty([200u8; 16]), &Kitty([3u8; 16]), random_seed);
+		let (winner, winner_kitty, loser, loser_kitty) =
+			if challenger_wins { (1, 0, 2, 1) } else { (2, 1, 1, 0) };
+		let winner_free = Balances::free_balance(winner);
+		let loser_free = Balances::free_balance(loser);
+
+		assert_ok!(KittiesModule::resolve_battle(RuntimeOrigin::signed(3), 0));
+
+		// 胜者取回自己的赌注并获得败者的赌注, 两者均只保留kitty的质押
+		assert_eq!(Balances::free_balance(winner), winner_free + 1000);
+		assert_eq!(Balances::free_balance(loser), loser_free);
+		assert_eq!(Balances::reserved_balance(winner), 1000);
+		assert_eq!(Balances::reserved_balance(loser), 1000);
+
+		assert_eq!(BattleRecords::<Test>::get(winner_kitty), BattleRecord { wins: 1, losses: 0 });
+		assert_eq!(BattleRecords::<Test>::get(loser_kitty), BattleRecord { wins: 0, losses: 1 });
+		assert_eq!(Battles::<Test>::get(0), None);
+		assert_eq!(ActiveBattles::<Test>::get(0), None);
+		assert_eq!(ActiveBattles::<Test>::get(1), None);
+		assert_eq!(BlockSeeds::<Test>::get(83), None);
+		System::assert_last_event(
+			Event::BattleResolved(0, winner, winner_kitty, loser_kitty, 500).into(),
+		);
+	})
+}
+
+/// 败者质押在对战期间被扣减, 胜者按实际转出的赌注结算, 并在事件中记录实际金额
+#[test]
+fn resolve_battle_with_reduced_loser_reserve() {
+	new_test_ext_with_kitties(vec![(1, [200u8; 16]), (2, [3u8; 16])]).execute_with(|| {
+		assert_ok!(KittiesModule::challenge(RuntimeOrigin::signed(1), 0, 1, 500));
+		assert_ok!(KittiesModule::accept_challenge(RuntimeOrigin::signed(2), 0));
+		System::set_block_number(83);
+		KittiesModule::on_initialize(83);
+		let random_seed =
+			BlakeTwo256::hash_of(&(BlockSeeds::<Test>::get(83).unwrap(), b"kitties/battle", 0u32));
+		let challenger_wins =
+			KittiesModule::battle_outcome(&Kitty([200u8; 16]), &Kitty([3u8; 16]), random_seed);
+		let (winner, winner_kitty, loser, loser_kitty) =
+			if challenger_wins { (1, 0, 2, 1) } else { (2, 1, 1, 0) };
+
+		// 败者的质押被削减到只剩300
+		let _ = Balances::slash_reserved(&loser, 1200);
+		assert_eq!(Balances::reserved_balance(loser), 300);
+		let winner_free = Balances::free_balance(winner);
+
+		assert_ok!(KittiesModule::resolve_battle(RuntimeOrigin::signed(3), 0));
+		assert_eq!(Balances::free_balance(winner), winner_free + 500 + 300);
+		assert_eq!(Balances::reserved_balance(loser), 0);
+		System::assert_last_event(
+			Event::BattleResolved(0, winner, winner_kitty, loser_kitty, 300).into(),
+		);
+	})
+}
+
+/// 在不同区块结算同一场对战, 胜者相同
+#[test]
+fn resolve_battle_at_any_later_block_gives_same_winner() {
+	new_test_ext_with_kitties(vec![(1, [100u8; 16]), (2, [100u8; 16])]).execute_with(|| {
+		assert_ok!(KittiesModule::challenge(RuntimeOrigin::signed(1), 0, 1, 500));
+		assert_ok!(KittiesModule::accept_challenge(RuntimeOrigin::signed(2), 0));
+
+		System::set_block_number(83);
+		KittiesModule::on_initialize(83);
+
+		// 在区块83结算的胜者, 之后回滚
+		let resolve = || {
+			frame_support::storage::with_transaction(|| {
+				let result = KittiesModule::resolve_battle(RuntimeOrigin::signed(3), 0)
+					.map(|_| BattleRecords::<Test>::get(0).wins);
+				TransactionOutcome::Rollback(result)
+			})
+		};
+		let challenger_wins_at_83 = resolve().expect("resolve works");
+
+		// 之后的区块产生了新的随机数, 延后结算胜者不变
+		for n in 84..=200u64 {
+			System::set_block_number(n);
+			System::set_parent_hash(H256::repeat_byte(n as u8));
+			RandomnessCollectiveFlip::on_initialize(n);
+		}
+		assert_ne!(
+			RandomnessCollectiveFlip::random(b"kitties/seed").0,
+			BlockSeeds::<Test>::get(83).unwrap()
+		);
+		assert_eq!(resolve(), Ok(challenger_wins_at_83));
+	})
+}
+
+/// 对战结果由dna战力及随机数共同决定, 相同输入结果相同
+#[test]
+fn battle_outcome_is_deterministic() {
+	new_test_ext().execute_with(|| {
+		let strong = Kitty([255u8; 16]);
+		let weak = Kitty([0u8; 16]);
+
+		let mut strong_wins = 0;
+		for seed in 0..100u8 {
+			let random_seed = H256::repeat_byte(seed);
+			let outcome = KittiesModule::battle_outcome(&strong, &weak, random_seed);
+			assert_eq!(outcome, KittiesModule::battle_outcome(&strong, &weak, random_seed));
+			if outcome {
+				strong_wins += 1;
+			}
+		}
+
+		// 战力为4081对1, 强者几乎总是获胜
+		assert!(strong_wins >= 95);
+	})
+}
+
+/// 对战中的kitty不能转移、出借、销毁或再次参与对战, 取消挑战后解除锁定
+#[test]
+fn battle_locks_kitty_and_cancel_works() {
+	new_test_ext_with_kitties(vec![(1, [0u8; 16]), (2, [1u8; 16]), (2, [2u8; 16])]).execute_with(
+		|| {
+			// 不能挑战自己的kitty
+			assert_noop!(
+				KittiesModule::challenge(RuntimeOrigin::signed(2), 1, 2, 100),
+				Error::<Test>::BattleWithSelf
+			);
+			// 赌注不足
+			assert_noop!(
+				KittiesModule::challenge(RuntimeOrigin::signed(1), 0, 1, 10000),
+				Error::<Test>::TokenNotEnough
+			);
+
+			assert_ok!(KittiesModule::challenge(RuntimeOrigin::signed(1), 0, 1, 100));
+
+			assert_noop!(
+				KittiesModule::challenge(RuntimeOrigin::signed(1), 0, 2, 100),
+				Error::<Test>::KittyInBattle
+			);
+			assert_noop!(
+				KittiesModule::transfer(RuntimeOrigin::signed(1), 0, 3),
+				Error::<Test>::KittyInBattle
+			);
+			assert_noop!(
+				KittiesModule::offer_lease(RuntimeOrigin::signed(1), 0, 3, 10, 10),
+				Error::<Test>::KittyInBattle
+			);
+			assert_noop!(KittiesModule::do_burn(0, Some(&1)), Error::<Test>::KittyInBattle);
+			assert!(!<KittiesModule as nonfungible::Inspect<u64>>::can_transfer(&0));
+
+			// 其他账户不能取消挑战, 被挑战kitty的owner可以拒绝
+			assert_noop!(
+				KittiesModule::cancel_challenge(RuntimeOrigin::signed(3), 0),
+				Error::<Test>::NotOwner
+			);
+			assert_ok!(KittiesModule::cancel_challenge(RuntimeOrigin::signed(2), 0));
+			assert_eq!(Balances::reserved_balance(1), 1000);
+			assert_eq!(ActiveBattles::<Test>::get(0), None);
+			assert_noop!(
+				KittiesModule::accept_challenge(RuntimeOrigin::signed(2), 0),
+				Error::<Test>::BattleNotExist
+			);
+
+			// 解除锁定后可以正常转移
+			assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(1), 0, 2));
+		},
+	);
+}
+
+/// 批量创建、转移及销毁kitty成功
+#[test]
+fn batch_operations_work() {
+	new_test_ext().execute_with(|| {
+		// 账户1批量创建3个kitty, 一次性质押3000
+		assert_ok!(KittiesModule::create_batch(RuntimeOrigin::signed(1), 3));
+		assert_eq!(KittyCount::<Test>::get(), 3);
+		assert_eq!(Balances::reserved_balance(1), 3000);
+		assert_eq!(KittyAll::<Test>::get(1).len(), 3);
+		// 同一交易中创建的kitty dna各不相同
+		assert_ne!(Kitties::<Test>::get(0), Kitties::<Test>::get(1));
+		System::assert_last_event(Event::KittiesBatchCreated(1, 3).into());
+
+		// 批量转移kitty 0和2给账户2
+		assert_ok!(KittiesModule::transfer_batch(RuntimeOrigin::signed(1), vec![0, 2], 2));
+		assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+		assert_eq!(KittyOwner::<Test>::get(2), Some(2));
+		assert_eq!(Balances::reserved_balance(1), 1000);
+		assert_eq!(Balances::reserved_balance(2), 2000);
+		System::assert_has_event(Event::KittyTransferred(1, 2, 2).into());
+		System::assert_last_event(Event::KittiesBatchTransferred(1, 2, 2).into());
+
+		// 账户2批量销毁收到的kitty
+		assert_ok!(KittiesModule::burn_batch(RuntimeOrigin::signed(2), vec![0, 2]));
+		assert_eq!(KittyCount::<Test>::get(), 1);
+		assert_eq!(Kitties::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		System::assert_last_event(Event::KittiesBatchBurned(2, 2).into());
+	})
+}
+
+/// 批量操作失败 - 数量为0、超过上限或其中任一kitty操作失败时全部回滚
+#[test]
+fn batch_operations_failed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::create_batch(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::EmptyBatch
+		);
+		assert_noop!(
+			KittiesModule::create_batch(RuntimeOrigin::signed(1), 6),
+			Error::<Test>::TooManyInBatch
+		);
+		// 账户3余额不足以质押2个kitty
+		assert_noop!(
+			KittiesModule::create_batch(RuntimeOrigin::signed(3), 2),
+			Error::<Test>::TokenNotEnough
+		);
+
+		assert_ok!(KittiesModule::create_batch(RuntimeOrigin::signed(1), 2));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(2)));
+
+		assert_noop!(
+			KittiesModule::transfer_batch(RuntimeOrigin::signed(1), vec![], 2),
+			Error::<Test>::EmptyBatch
+		);
+		// kitty 2属于账户2, 整批转移失败
+		assert_noop!(
+			KittiesModule::transfer_batch(RuntimeOrigin::signed(1), vec![0, 1, 2], 3),
+			Error::<Test>::NotOwner
+		);
+		// 重复的kitty id导致整批销毁失败
+		assert_noop!(
+			KittiesModule::burn_batch(RuntimeOrigin::signed(1), vec![0, 0]),
+			Error::<Test>::InvalidKittyId
+		);
+		assert_noop!(
+			KittiesModule::burn_batch(RuntimeOrigin::signed(1), vec![0, 1, 2]),
+			Error::<Test>::NotOwner
+		);
+	})
+}
+
+/// 由dna推导kitty性别
+#[test]
+fn kitty_gender_works() {
+	assert_eq!(Kitty(MALE_DNA).gender(), Gender::Male);
+	assert_eq!(Kitty(FEMALE_DNA).gender(), Gender::Female);
+	assert_eq!(Kitty([254u8; 16]).gender(), Gender::Male);
+	assert_eq!(Kitty([255u8; 16]).gender(), Gender::Female);
+}
+
+/// 繁殖Kitty失败 - 父母性别相同
+#[test]
+fn breed_kitty_failed_incompatible_gender() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (1, [2u8; 16]), (1, FEMALE_DNA), (1, [3u8; 16])])
+		.execute_with(|| {
+			assert_noop!(
+				KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1),
+				Error::<Test>::IncompatibleGender
+			);
+			assert_noop!(
+				KittiesModule::breed(RuntimeOrigin::signed(1), 2, 3),
+				Error::<Test>::IncompatibleGender
+			);
+		})
+}
+
+/// 繁殖Kitty失败 - 父子或兄弟姐妹之间不能繁殖
+#[test]
+fn breed_kitty_failed_too_closely_related() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (1, FEMALE_DNA), (1, [2u8; 16])]).execute_with(
+		|| {
+			// kitty 3和kitty 4均为kitty 0与kitty 1的后代
+			assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1));
+			assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1));
+			// kitty 5为kitty 2与kitty 1的后代, 与kitty 3、4同母
+			assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(1), 2, 1));
+
+			// 指定后代的性别以排除性别限制
+			Kitties::<Test>::insert(3, Kitty(MALE_DNA));
+			Kitties::<Test>::insert(4, Kitty(FEMALE_DNA));
+			Kitties::<Test>::insert(5, Kitty(FEMALE_DNA));
+
+			// 父子
+			assert_noop!(
+				KittiesModule::breed(RuntimeOrigin::signed(1), 1, 3),
+				Error::<Test>::TooCloselyRelated
+			);
+			assert_noop!(
+				KittiesModule::breed(RuntimeOrigin::signed(1), 4, 0),
+				Error::<Test>::TooCloselyRelated
+			);
+			// 同父同母
+			assert_noop!(
+				KittiesModule::breed(RuntimeOrigin::signed(1), 3, 4),
+				Error::<Test>::TooCloselyRelated
+			);
+			// 同母异父
+			assert_noop!(
+				KittiesModule::breed(RuntimeOrigin::signed(1), 3, 5),
+				Error::<Test>::TooCloselyRelated
+			);
+
+			// 无亲缘关系的kitty可以繁殖
+			assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(1), 2, 4));
+		},
+	);
+}
+
+/// 交换指定的kitty成功, 补贴转给接受者
+#[test]
+fn swap_specific_kitty_works() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA)]).execute_with(|| {
+		// 账户1提议用kitty 0交换kitty 1, 补贴100, 有效期10个区块
+		assert_ok!(KittiesModule::propose_swap(
+			RuntimeOrigin::signed(1),
+			0,
+			SwapTarget::Kitty(1),
+			100,
+			10
+		));
+		assert_eq!(Balances::reserved_balance(1), 1100);
+		assert_noop!(
+			KittiesModule::propose_swap(RuntimeOrigin::signed(1), 0, SwapTarget::Kitty(1), 0, 10),
+			Error::<Test>::SwapAlreadyProposed
+		);
+
+		let free_2 = Balances::free_balance(2);
+		assert_ok!(KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1));
+
+		assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+		assert_eq!(KittyOwner::<Test>::get(1), Some(1));
+		assert_eq!(KittyAll::<Test>::get(1).into_inner(), vec![Kitty(FEMALE_DNA)]);
+		assert_eq!(KittyAll::<Test>::get(2).into_inner(), vec![Kitty(MALE_DNA)]);
+		assert_eq!(Balances::free_balance(2), free_2 + 100);
+		assert_eq!(Balances::reserved_balance(1), 1000);
+		assert_eq!(Balances::reserved_balance(2), 1000);
+		assert_eq!(SwapOffers::<Test>::get(0), None);
+		System::assert_last_event(Event::SwapAccepted(1, 2, 0, 1, 100).into());
+	})
+}
+
+/// 提议者质押的补贴被削减后, 交换失败且双方kitty不变
+#[test]
+fn accept_swap_failed_top_up_unavailable() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA)]).execute_with(|| {
+		assert_ok!(KittiesModule::propose_swap(
+			RuntimeOrigin::signed(1),
+			0,
+			SwapTarget::Kitty(1),
+			100,
+			10
+		));
+
+		// 账户1的质押被削减到只剩50, 不足以支付补贴
+		let _ = Balances::slash_reserved(&1, 1050);
+		assert_noop!(
+			KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1),
+			Error::<Test>::SwapTopUpUnavailable
+		);
+		assert_eq!(KittyOwner::<Test>::get(0), Some(1));
+		assert_eq!(KittyOwner::<Test>::get(1), Some(2));
+		assert!(SwapOffers::<Test>::get(0).is_some());
+	})
+}
+
+/// 按特征过滤交换kitty, 不满足条件的kitty不能接受
+#[test]
+fn swap_by_traits_works() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, [3u8; 16]), (2, [5u8; 16])]).execute_with(
+		|| {
+			// 期望换得首字节低4位为5的母kitty
+			let mut mask = [0u8; 16];
+			let mut value = [0u8; 16];
+			mask[0] = 0x0f;
+			value[0] = 5;
+			let target = SwapTarget::Traits { mask, value, gender: Some(Gender::Female) };
+			assert_ok!(KittiesModule::propose_swap(RuntimeOrigin::signed(1), 0, target, 0, 10));
+
+			assert_noop!(
+				KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1),
+				Error::<Test>::SwapTargetMismatch
+			);
+			// 不能用别人的kitty接受, 提议者也不能接受自己的要约
+			assert_noop!(
+				KittiesModule::accept_swap(RuntimeOrigin::signed(3), 0, 2),
+				Error::<Test>::NotOwner
+			);
+			assert_noop!(
+				KittiesModule::accept_swap(RuntimeOrigin::signed(1), 0, 0),
+				Error::<Test>::SwapWithSelf
+			);
+
+			assert_ok!(KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 2));
+			assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+			assert_eq!(KittyOwner::<Test>::get(2), Some(1));
+		},
+	);
+}
+
+/// 交换要约过期后不能接受, 提议者随时可取消, 过期后任何账户均可清除
+#[test]
+fn swap_expiry_and_cancel_works() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA)]).execute_with(|| {
+		assert_noop!(
+			KittiesModule::propose_swap(RuntimeOrigin::signed(1), 0, SwapTarget::Kitty(1), 0, 101),
+			Error::<Test>::InvalidSwapDuration
+		);
+		assert_noop!(
+			KittiesModule::propose_swap(RuntimeOrigin::signed(2), 0, SwapTarget::Kitty(1), 0, 10),
+			Error::<Test>::NotOwner
+		);
+
+		// 区块1提议, 区块11过期
+		assert_ok!(KittiesModule::propose_swap(
+			RuntimeOrigin::signed(1),
+			0,
+			SwapTarget::Kitty(1),
+			100,
+			10
+		));
+		assert_noop!(
+			KittiesModule::cancel_swap(RuntimeOrigin::signed(3), 0),
+			Error::<Test>::SwapOfferNotExpired
+		);
+
+		System::set_block_number(12);
+		assert_noop!(
+			KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1),
+			Error::<Test>::SwapOfferExpired
+		);
+		assert_ok!(KittiesModule::cancel_swap(RuntimeOrigin::signed(3), 0));
+		assert_eq!(Balances::reserved_balance(1), 1000);
+		System::assert_last_event(Event::SwapCancelled(1, 0).into());
+
+		// 提议者在有效期内取消
+		assert_ok!(KittiesModule::propose_swap(
+			RuntimeOrigin::signed(1),
+			0,
+			SwapTarget::Kitty(1),
+			100,
+			10
+		));
+		assert_ok!(KittiesModule::cancel_swap(RuntimeOrigin::signed(1), 0));
+		assert_noop!(
+			KittiesModule::cancel_swap(RuntimeOrigin::signed(1), 0),
+			Error::<Test>::SwapOfferNotExist
+		);
+	})
+}
+
+/// 对战中的kitty不能提议交换, 要约双方的kitty进入对战后不能接受交换
+#[test]
+fn swap_rejects_kitty_in_battle() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA), (2, [3u8; 16])]).execute_with(
+		|| {
+			assert_ok!(KittiesModule::propose_swap(
+				RuntimeOrigin::signed(1),
+				0,
+				SwapTarget::Kitty(1),
+				100,
+				10
+			));
+
+			// 提议者的kitty 0发起对战后被锁定
+			assert_ok!(KittiesModule::challenge(RuntimeOrigin::signed(1), 0, 2, 100));
+			assert_noop!(
+				KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1),
+				Error::<Test>::KittyInBattle
+			);
+			assert_ok!(KittiesModule::cancel_challenge(RuntimeOrigin::signed(1), 0));
+
+			// 接受者的kitty 1发起对战后被锁定, 也不能再提议交换
+			assert_ok!(KittiesModule::challenge(RuntimeOrigin::signed(2), 1, 0, 100));
+			assert_noop!(
+				KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1),
+				Error::<Test>::KittyInBattle
+			);
+			assert_noop!(
+				KittiesModule::propose_swap(
+					RuntimeOrigin::signed(2),
+					1,
+					SwapTarget::Kitty(0),
+					0,
+					10
+				),
+				Error::<Test>::KittyInBattle
+			);
+
+			// 解除锁定后可以交换
+			assert_ok!(KittiesModule::cancel_challenge(RuntimeOrigin::signed(2), 1));
+			assert_ok!(KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1));
+			assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+		},
+	);
+}
+
+/// 转移提议交换的kitty时清除要约并退还补贴
+#[test]
+fn transfer_clears_swap_offer() {
+	new_test_ext_with_kitties(vec![(1, MALE_DNA), (2, FEMALE_DNA)]).execute_with(|| {
+		assert_ok!(KittiesModule::propose_swap(
+			RuntimeOrigin::signed(1),
+			0,
+			SwapTarget::Kitty(1),
+			100,
+			10
+		));
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(1), 0, 2));
+
+		assert_eq!(SwapOffers::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_noop!(
+			KittiesModule::accept_swap(RuntimeOrigin::signed(2), 0, 1),
+			Error::<Test>::SwapOfferNotExist
+		);
+	})
+}
+
+/// 迁移到v1 - 写入存储版本并回填KittyCount、OwnedKitties, 重复执行无影响
+#[test]
+fn migration_to_v1_backfills_kitty_count() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		// 引入KittyCount之前的链: 存在kitty但计数为0
+		for kitty_id in 0..3u32 {
+			Kitties::<Test>::insert(kitty_id, Kitty([kitty_id as u8; 16]));
+			KittyOwner::<Test>::insert(kitty_id, 1);
+		}
+		assert_eq!(KittyCount::<Test>::get(), 0);
+		assert_eq!(StorageVersion::get::<KittiesModule>(), 0);
+
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<KittiesModule>(), 1);
+		assert_eq!(KittyCount::<Test>::get(), 3);
+		assert_eq!(KittyOwner::<Test>::try_get(0), Ok(1));
+		assert_eq!(OwnedKitties::<Test>::iter_key_prefix(1).count(), 3);
+
+		// 再次执行不会改变版本和计数
+		Kitties::<Test>::insert(9, Kitty([9u8; 16]));
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<KittiesModule>(), 1);
+		assert_eq!(KittyCount::<Test>::get(), 3);
+	})
+}
+
+/// 迁移到v1 - 为升级前提交的铸造承诺登记随机数, 揭示区块已过去的直接固定
+#[test]
+fn migration_to_v1_requests_seeds_for_pending_commits() {
+	use frame_support::traits::OnRuntimeUpgrade;
+
+	new_test_ext().execute_with(|| {
+		let salt = [7u8; 32];
+		let commitment = BlakeTwo256::hash_of(&(1u64, salt));
+		PendingMints::<Test>::insert(1, MintCommit { commitment, committed_at: 10 });
+		PendingMints::<Test>::insert(2, MintCommit { commitment, committed_at: 50 });
+
+		System::set_block_number(100);
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		// 账户1的揭示区块92已过去, 随机数立即固定并可以揭示
+		assert_eq!(SeedRequests::<Test>::get(92), 1);
+		assert!(BlockSeeds::<Test>::contains_key(92));
+		assert_ok!(KittiesModule::reveal_create(RuntimeOrigin::signed(1), salt));
+
+		// 账户2的揭示区块132尚未到达, 由on_initialize固定
+		assert_eq!(SeedRequests::<Test>::get(132), 1);
+		assert_eq!(BlockSeeds::<Test>::get(132), None);
+	})
+}
@@ -0,0 +1,99 @@
+//! RPC interface serving proof of existence contents from offchain indexing.
+//!
+//! The contents only exist on nodes with offchain indexing enabled, which the node
+//! template always forces. Other nodes answer every request with [`Error::IndexingDisabled`].
+
+use std::sync::Arc;
+
+use codec::Decode;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_poe::{offchain_key, ClaimContent};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sp_core::{offchain::OffchainStorage, Bytes};
+
+/// A claim's indexed content as returned over RPC, decoded as UTF-8.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcClaimContent {
+	pub name: String,
+	pub descriptor: String,
+}
+
+impl From<ClaimContent> for RpcClaimContent {
+	fn from(content: ClaimContent) -> Self {
+		RpcClaimContent {
+			name: String::from_utf8_lossy(&content.name).into_owned(),
+			descriptor: String::from_utf8_lossy(&content.descriptor).into_owned(),
+		}
+	}
+}
+
+#[rpc(client, server)]
+pub trait PoeApi {
+	#[method(name = "poe_claimContent")]
+	fn claim_content(&self, claim: Bytes) -> RpcResult<Option<RpcClaimContent>>;
+}
+
+/// Provides RPC methods to read indexed claim contents.
+pub struct Poe<T: OffchainStorage> {
+	storage: Arc<RwLock<T>>,
+	indexing_enabled: bool,
+}
+
+impl<T: OffchainStorage> Poe<T> {
+	/// Creates a new instance of the Poe RPC helper.
+	///
+	/// `indexing_enabled` should reflect the node's offchain indexing setting.
+	pub fn new(storage: T, indexing_enabled: bool) -> Self {
+		Self { storage: Arc::new(RwLock::new(storage)), indexing_enabled }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The indexed content could not be decoded.
+	DecodeError,
+	/// The node was started without offchain indexing.
+	IndexingDisabled,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::DecodeError => 1,
+			Error::IndexingDisabled => 2,
+		}
+	}
+}
+
+impl<T: OffchainStorage + 'static> PoeApiServer for Poe<T> {
+	fn claim_content(&self, claim: Bytes) -> RpcResult<Option<RpcClaimContent>> {
+		if !self.indexing_enabled {
+			return Err(CallError::Custom(ErrorObject::owned(
+				Error::IndexingDisabled.into(),
+				"Offchain indexing is disabled, restart the node with `--enable-offchain-indexing true`.",
+				None::<()>,
+			))
+			.into())
+		}
+
+		let raw = self.storage.read().get(sp_offchain::STORAGE_PREFIX, &offchain_key(&claim));
+
+		raw.map(|raw| {
+			ClaimContent::decode(&mut &raw[..]).map(Into::into).map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::DecodeError.into(),
+					"Unable to decode claim content.",
+					Some(format!("{:?}", e)),
+				))
+				.into()
+			})
+		})
+		.transpose()
+	}
+}
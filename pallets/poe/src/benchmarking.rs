@@ -38,5 +38,18 @@ benchmarks! {
 		assert!(Pallet::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone()).is_ok());
 	}: _(RawOrigin::Signed(caller), claim, target)
 
+	index_claim_content {
+		let d in 0 .. T::MaxClaimLength::get();
+		let c in 0 .. T::MaxContentLength::get() * 2;
+		let claim = vec![0; d as usize];
+		let name = vec![1; (c / 2) as usize];
+		let descriptor = vec![2; (c - c / 2) as usize];
+		let caller: T::AccountId = whitelisted_caller();
+		assert!(Pallet::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone()).is_ok());
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), name, descriptor)
+	verify {
+		assert_last_event::<T>(Event::ClaimContentIndexed(caller, claim).into())
+	}
+
 	impl_benchmark_test_suite!(PoeModule, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -13,8 +13,27 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
 pub mod weights;
 
+use codec::{Decode, Encode};
+use sp_std::prelude::*;
+
+// 链下索引中存证内容所用key的前缀
+pub const OFFCHAIN_PREFIX: &[u8] = b"poe::content::";
+
+// 写入链下索引的存证内容：文档名称与描述
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+pub struct ClaimContent {
+	pub name: Vec<u8>,
+	pub descriptor: Vec<u8>,
+}
+
+// 存证内容在链下索引中的key, 节点的RPC也通过它读取
+pub fn offchain_key(claim: &[u8]) -> Vec<u8> {
+	(OFFCHAIN_PREFIX, claim).encode()
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -29,15 +48,24 @@ pub mod pallet {
 		#[pallet::constant]
 		// 存证最大能接受的长度限制
 		type MaxClaimLength: Get<u32>;
+		#[pallet::constant]
+		// 存证内容(名称、描述)最大能接受的长度限制
+		type MaxContentLength: Get<u32>;
 		// 该通用的关联类型，在runtime进行配置接口实现时，会把runtime定义的Event设置在这个类型里
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		// 仲裁者origin, 可强制撤销任意存证, 如root或议会多数
+		type ArbiterOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 		type WeightInfo: WeightInfo;
 	}
 
+	// 存储版本，存储结构变更时递增并在migrations中添加对应的迁移
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	// 定义模块所需的结构体
 	#[pallet::pallet]
 	// 模块会定义自己所需的存储项，因此需要pallet::generate_store宏，它包括生成Store的trait接口
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// 存储
@@ -60,6 +88,9 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		ClaimCreated(T::AccountId, Vec<u8>),
 		ClaimRevoked(T::AccountId, Vec<u8>),
+		ClaimContentIndexed(T::AccountId, Vec<u8>),
+		// 仲裁者强制撤销存证, 参数为原owner
+		ClaimForceRevoked(T::AccountId, Vec<u8>),
 	}
 
 	/// error处理
@@ -69,6 +100,7 @@ pub mod pallet {
 		ClaimTooLong,
 		ClaimNotExist,
 		NotClaimOwner,
+		ContentTooLong,
 	}
 
 	#[pallet::hooks]
@@ -123,8 +155,9 @@ pub mod pallet {
 			// 确认发送方sender与存证owner一致，否则返回Error
 			ensure!(owner == sender, Error::<T>::NotClaimOwner);
 
-			// 删除存证
+			// 删除存证，同时清除链下索引中的存证内容
 			Proofs::<T>::remove(&bounded_claim);
+			sp_io::offchain_index::clear(&offchain_key(&claim));
 
 			// 触发revoke事件
 			Self::deposit_event(Event::ClaimRevoked(sender, claim));
@@ -162,5 +195,69 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::index_claim_content(
+			claim.len() as u32,
+			name.len().saturating_add(descriptor.len()) as u32,
+		))]
+		// 将存证对应的文档名称与描述写入链下索引，只保存在开启了链下索引的节点本地，不占用链上存储
+		pub fn index_claim_content(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			name: Vec<u8>,
+			descriptor: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			// 判断是否是签名用户
+			let sender = ensure_signed(origin)?;
+
+			// 校验存证内容的hash值，包括所需要的最大长度
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			// 名称和描述的长度不能超过限制
+			let max_content = T::MaxContentLength::get() as usize;
+			ensure!(
+				name.len() <= max_content && descriptor.len() <= max_content,
+				Error::<T>::ContentTooLong
+			);
+
+			// 只有存证的owner才能写入存证内容
+			let (owner, _) = Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			// 写入链下索引，再次写入会覆盖之前的内容
+			let content = ClaimContent { name, descriptor };
+			sp_io::offchain_index::set(&offchain_key(&claim), &content.encode());
+
+			// 触发索引事件
+			Self::deposit_event(Event::ClaimContentIndexed(sender, claim));
+
+			Ok(().into())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::revoke_claim(claim.len() as u32))]
+		// 仲裁者强制撤销存证，用于处理侵权或争议的存证，无需owner同意
+		pub fn force_revoke(origin: OriginFor<T>, claim: Vec<u8>) -> DispatchResultWithPostInfo {
+			// 只有仲裁者origin才能强制撤销
+			T::ArbiterOrigin::ensure_origin(origin)?;
+
+			// 校验存证内容的hash值，包括所需要的最大长度
+			let bounded_claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.clone())
+				.map_err(|_| Error::<T>::ClaimTooLong)?;
+
+			// 只有已存储的存证才能被撤销
+			let (owner, _) = Proofs::<T>::get(&bounded_claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			// 删除存证，同时清除链下索引中的存证内容
+			Proofs::<T>::remove(&bounded_claim);
+			sp_io::offchain_index::clear(&offchain_key(&claim));
+
+			// 触发强制撤销事件
+			Self::deposit_event(Event::ClaimForceRevoked(owner, claim));
+
+			Ok(().into())
+		}
 	}
 }
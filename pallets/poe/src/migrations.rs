@@ -0,0 +1,46 @@
+// 存储迁移
+
+// v1：存储结构与之前一致, 只为声明存储版本之前的链写入版本号
+// 之后的迁移以链上版本为1作为前提
+pub mod v1 {
+	use crate::{Config, Pallet, Proofs};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{GetStorageVersion, OnRuntimeUpgrade},
+	};
+	use sp_std::marker::PhantomData;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::prelude::*;
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			// 已经迁移过则跳过
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		// 升级前记录链上版本与存证数量
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			let on_chain = Pallet::<T>::on_chain_storage_version();
+			ensure!(on_chain <= 1, "poe: unexpected storage version");
+			Ok((on_chain, Proofs::<T>::iter().count() as u32).encode())
+		}
+
+		// 升级后版本为1, 存证没有丢失
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let (_, proofs): (StorageVersion, u32) =
+				Decode::decode(&mut &state[..]).map_err(|_| "poe: invalid pre_upgrade state")?;
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "poe: storage version not 1");
+			ensure!(Proofs::<T>::iter().count() as u32 == proofs, "poe: proofs lost");
+			Ok(())
+		}
+	}
+}
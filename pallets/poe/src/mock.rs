@@ -51,7 +51,9 @@ impl system::Config for Test {
 
 impl pallet_poe::Config for Test {
 	type MaxClaimLength = ConstU32<512>;
+	type MaxContentLength = ConstU32<1024>;
 	type RuntimeEvent = RuntimeEvent;
+	type ArbiterOrigin = frame_system::EnsureRoot<u64>;
 	type WeightInfo = ();
 }
 
@@ -106,3 +106,147 @@ fn transfer_claim_failed_when_claim_already_transfer() {
 		);
 	})
 }
+
+/// 写入存证内容到链下索引
+#[test]
+fn index_claim_content_works() {
+	let mut t = new_test_ext();
+	let claim = vec![0, 1];
+
+	t.execute_with(|| {
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::index_claim_content(
+			RuntimeOrigin::signed(1),
+			claim.clone(),
+			b"report.pdf".to_vec(),
+			b"Quarterly report".to_vec(),
+		));
+	});
+
+	// 链下索引在区块执行结束后才写入链下数据库
+	t.persist_offchain_overlay();
+	let content = t
+		.offchain_db()
+		.get(&offchain_key(&claim))
+		.map(|raw| ClaimContent::decode(&mut &raw[..]).unwrap());
+	assert_eq!(
+		content,
+		Some(ClaimContent {
+			name: b"report.pdf".to_vec(),
+			descriptor: b"Quarterly report".to_vec(),
+		})
+	);
+
+	// 撤销存证时清除存证内容
+	t.execute_with(|| {
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()));
+	});
+	t.persist_offchain_overlay();
+	assert_eq!(t.offchain_db().get(&offchain_key(&claim)), None);
+}
+
+/// 写入存证内容失败 - 存证不存在或不属于自己
+#[test]
+fn index_claim_content_failed_when_not_claim_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+
+		assert_noop!(
+			PoeModule::index_claim_content(
+				RuntimeOrigin::signed(1),
+				claim.clone(),
+				b"name".to_vec(),
+				vec![]
+			),
+			Error::<Test>::ClaimNotExist
+		);
+
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::index_claim_content(
+				RuntimeOrigin::signed(2),
+				claim.clone(),
+				b"name".to_vec(),
+				vec![]
+			),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+/// 写入存证内容失败 - 内容过长
+#[test]
+fn index_claim_content_failed_when_content_too_long() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::index_claim_content(
+				RuntimeOrigin::signed(1),
+				claim.clone(),
+				vec![1; 1025],
+				vec![]
+			),
+			Error::<Test>::ContentTooLong
+		);
+	})
+}
+
+/// 仲裁者强制撤销存证
+#[test]
+fn force_revoke_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::force_revoke(RuntimeOrigin::root(), claim.clone()));
+
+		let bounded_claim =
+			BoundedVec::<u8, <Test as Config>::MaxClaimLength>::try_from(claim.clone()).unwrap();
+		assert_eq!(Proofs::<Test>::get(&bounded_claim), None);
+	})
+}
+
+/// 强制撤销存证失败 - 非仲裁者或存证不存在
+#[test]
+fn force_revoke_failed_when_not_arbiter() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+
+		assert_noop!(
+			PoeModule::force_revoke(RuntimeOrigin::root(), claim.clone()),
+			Error::<Test>::ClaimNotExist
+		);
+
+		// 存证owner自己也不能使用强制撤销
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_noop!(
+			PoeModule::force_revoke(RuntimeOrigin::signed(1), claim.clone()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+/// 迁移到v1 - 只写入存储版本, 已有的存证不变, 重复执行无影响
+#[test]
+fn migration_to_v1_sets_storage_version() {
+	use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_eq!(StorageVersion::get::<PoeModule>(), 0);
+
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<PoeModule>(), 1);
+
+		let bounded_claim =
+			BoundedVec::<u8, <Test as Config>::MaxClaimLength>::try_from(claim.clone()).unwrap();
+		assert_eq!(Proofs::<Test>::get(&bounded_claim), Some((1, 0)));
+
+		// 再次执行不会改变版本
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get::<PoeModule>(), 1);
+	})
+}
@@ -5,6 +5,8 @@
 //! DATE: 2023-04-04, STEPS: `20`, REPEAT: 10, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! HOSTNAME: `amesy-CW65S`, CPU: `Intel(R) Core(TM) i7-4710MQ CPU @ 2.50GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
+//!
+//! `index_claim_content` has a benchmark but was not part of this run, rerun the command below.
 
 // Executed Command:
 // ./target/release/node-template
@@ -41,6 +43,7 @@ pub trait WeightInfo {
 	fn create_claim(d: u32, ) -> Weight;
 	fn revoke_claim(d: u32, ) -> Weight;
 	fn transfer_claim(d: u32, ) -> Weight;
+	fn index_claim_content(d: u32, c: u32, ) -> Weight;
 }
 
 /// Weights for pallet_poe using the Substrate node and recommended hardware.
@@ -76,6 +79,15 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: PoeModule Proofs (r:1 w:0)
+	/// The range of component `d` is `[0, 512]`.
+	/// The range of component `c` is `[0, 2048]`.
+	fn index_claim_content(d: u32, c: u32, ) -> Weight {
+		Weight::from_ref_time(42_621_023)
+			.saturating_add(Weight::from_ref_time(26_071).saturating_mul(d.into()))
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
 }
 
 // For backwards compatibility and tests
@@ -110,4 +122,13 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1))
 			.saturating_add(RocksDbWeight::get().writes(1))
 	}
-}
\ No newline at end of file
+	// Storage: PoeModule Proofs (r:1 w:0)
+	/// The range of component `d` is `[0, 512]`.
+	/// The range of component `c` is `[0, 2048]`.
+	fn index_claim_content(d: u32, c: u32, ) -> Weight {
+		Weight::from_ref_time(42_621_023)
+			.saturating_add(Weight::from_ref_time(26_071).saturating_mul(d.into()))
+			.saturating_add(Weight::from_ref_time(2_000).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(1))
+	}
+}
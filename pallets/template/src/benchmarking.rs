@@ -5,15 +5,21 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Template;
 use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::BoundedVec;
 use frame_system::RawOrigin;
+use sp_std::vec;
 
 benchmarks! {
 	do_something {
 		let s in 0 .. 100;
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+		Something::<T>::insert(&caller, 0);
+		// Worst case: the previous value pushes the oldest one out of a full history.
+		let history = vec![0; T::MaxHistory::get() as usize];
+		History::<T>::insert(&caller, BoundedVec::truncate_from(history));
+	}: _(RawOrigin::Signed(caller.clone()), s)
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert_eq!(Something::<T>::get(&caller), Some(s));
 	}
 
 	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
@@ -11,12 +11,97 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
+
+use codec::{Decode, Encode};
 use frame_system::offchain::{
-	SubmitTransaction,
+	AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendUnsignedTransaction,
+	SignedPayload, Signer, SigningTypes,
 };
+use lite_json::json::JsonValue;
+use sp_core::crypto::KeyTypeId;
 use sp_runtime::{
+	offchain::{
+		http,
+		storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
+		storage_lock::{BlockAndTime, StorageLock},
+		Duration,
+	},
+	traits::IdentifyAccount,
 	transaction_validity::{InvalidTransaction, TransactionValidity, ValidTransaction},
+	RuntimeDebug,
 };
+use sp_std::vec::Vec;
+
+/// Offchain persistent storage key of the price feed URL.
+///
+/// The value is the SCALE-encoded URL bytes, e.g. set with the `offchain_localStorageSet`
+/// RPC. The feed must return a JSON object with a numeric `USD` field.
+pub const PRICE_URL_KEY: &[u8] = b"template::price-url";
+
+/// Offchain persistent storage key of the block the offchain worker last ran at.
+pub const LAST_RUN_KEY: &[u8] = b"template::last-run";
+
+/// Offchain persistent storage key of the lock held while the offchain worker runs.
+pub const LOCK_KEY: &[u8] = b"template::lock";
+
+/// The lock is released after this many blocks or milliseconds, whichever comes last,
+/// in case a worker dies while holding it. Covers the 2s HTTP deadline.
+const LOCK_BLOCK_EXPIRATION: u32 = 3;
+const LOCK_TIMEOUT_EXPIRATION: u64 = 3_000;
+
+/// Key type of the offchain worker signing keys, `temp` in the node keystore.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"temp");
+
+/// Crypto used by the offchain worker to sign transactions.
+///
+/// An sr25519 app key of type [`KEY_TYPE`] must be present in the node keystore,
+/// e.g. inserted with `author_insertKey`, for the offchain worker to submit signed
+/// transactions.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		traits::Verify,
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct TemplateAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TemplateAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	// implemented for mock runtime in test
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature>
+		for TemplateAuthId
+	{
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// Payload of `submit_data_unsigned`, signed by an offchain worker authority key.
+///
+/// The block number ties the submission to a submission window, the public key
+/// identifies the authority that signed it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, scale_info::TypeInfo)]
+pub struct DataPayload<Public, BlockNumber> {
+	pub block_number: BlockNumber,
+	pub n: u64,
+	pub public: Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for DataPayload<T::Public, T::BlockNumber> {
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -24,13 +109,43 @@ pub mod pallet {
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config + frame_system::offchain::SendTransactionTypes<Call<Self>> {
+	pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The identifier type for the offchain worker signing key.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// Number of blocks that must pass between two accepted unsigned submissions.
+		#[pallet::constant]
+		type UnsignedInterval: Get<Self::BlockNumber>;
+
+		/// Priority of unsigned submissions in the transaction pool.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Maximum number of offchain worker authorities.
+		#[pallet::constant]
+		type MaxAuthorities: Get<u32>;
+
+		/// Number of recent prices kept for the moving average, at least 1.
+		#[pallet::constant]
+		type MaxPrices: Get<u32>;
+
+		/// Minimum number of blocks between two runs of the offchain worker on this node.
+		#[pallet::constant]
+		type OffchainInterval: Get<Self::BlockNumber>;
+
+		/// Number of past values kept per account.
+		#[pallet::constant]
+		type MaxHistory: Get<u32>;
 	}
 
 	// The pallet's runtime storage items.
@@ -39,7 +154,65 @@ pub mod pallet {
 	#[pallet::getter(fn something)]
 	// Learn more about declaring storage items:
 	// https://docs.substrate.io/main-docs/build/runtime-storage/#declaring-storage-items
-	pub type Something<T> = StorageValue<_, u32>;
+	pub type Something<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32>;
+
+	/// Values each account stored before its current one, oldest first.
+	///
+	/// Once full, the oldest value is dropped for every new one.
+	#[pallet::storage]
+	#[pallet::getter(fn history)]
+	pub type History<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u32, T::MaxHistory>, ValueQuery>;
+
+	/// The last value submitted by each offchain worker signing account.
+	#[pallet::storage]
+	#[pallet::getter(fn submitted_data)]
+	pub type SubmittedData<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u64>;
+
+	/// Accounts whose offchain worker keys may sign unsigned submissions.
+	#[pallet::storage]
+	#[pallet::getter(fn authorities)]
+	pub type Authorities<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxAuthorities>, ValueQuery>;
+
+	/// Most recent prices in cents, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn prices)]
+	pub type Prices<T: Config> = StorageValue<_, BoundedVec<u32, T::MaxPrices>, ValueQuery>;
+
+	/// Earliest block at which the next unsigned submission is accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn next_unsigned_at)]
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub authorities: Vec<T::AccountId>,
+		/// Initial value of each account, without history.
+		pub something: Vec<(T::AccountId, u32)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { authorities: Default::default(), something: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			let authorities = BoundedVec::<T::AccountId, T::MaxAuthorities>::try_from(
+				self.authorities.clone(),
+			)
+			.expect("too many genesis authorities");
+			<Authorities<T>>::put(authorities);
+
+			for (who, something) in &self.something {
+				<Something<T>>::insert(who, something);
+			}
+		}
+	}
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/main-docs/build/events-errors/
@@ -47,6 +220,12 @@ pub mod pallet {
 	#[pallet::generate_deposit(pub (super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		SomethingStored { something: u32, who: T::AccountId },
+		/// An offchain worker submitted data with a signed transaction or signed payload.
+		DataSubmitted { n: u64, who: T::AccountId },
+		/// The offchain worker authority set was replaced.
+		AuthoritiesSet { authorities: Vec<T::AccountId> },
+		/// An authority submitted a new price in cents.
+		NewPrice { price: u32, who: T::AccountId },
 	}
 
 	// Errors inform users that something went wrong.
@@ -54,6 +233,8 @@ pub mod pallet {
 	pub enum Error<T> {
 		NoneValue,
 		StorageOverflow,
+		TooManyAuthorities,
+		NotAuthority,
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -62,15 +243,15 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
 		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://docs.substrate.io/main-docs/build/origins/
 			let who = ensure_signed(origin)?;
 
-			// Update storage.
-			<Something<T>>::put(something);
+			// Update the signer's value, keeping the previous one in its history.
+			Self::store_something(&who, something);
 
 			// Emit an event.
 			Self::deposit_event(Event::SomethingStored { something, who });
@@ -79,34 +260,96 @@ pub mod pallet {
 		}
 
 		#[pallet::call_index(1)]
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).ref_time())]
 		pub fn cause_error(origin: OriginFor<T>) -> DispatchResult {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
 
-			// Read a value from storage.
-			match <Something<T>>::get() {
+			// Read the signer's value from storage.
+			match <Something<T>>::get(&who) {
 				// Return an error if the value has not been set.
 				None => return Err(Error::<T>::NoneValue.into()),
 				Some(old) => {
 					// Increment the value read from storage; will error in the event of overflow.
 					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
 					// Update the value in storage with the incremented result.
-					<Something<T>>::put(new);
+					Self::store_something(&who, new);
 					Ok(())
 				},
 			}
 		}
 
+		/// Submit data with a payload signed by an authority key.
+		///
+		/// The signature and authority are checked in `validate_unsigned`.
 		#[pallet::call_index(2)]
-		#[pallet::weight(0)]
-		pub fn submit_data_unsigned(origin: OriginFor<T>, n: u64) -> DispatchResult {
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
+		pub fn submit_data_unsigned(
+			origin: OriginFor<T>,
+			payload: DataPayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResult {
 			ensure_none(origin)?;
 
-			log::info!("in submit_data_unsigned: {:?}", n);
+			let who = payload.public.into_account();
+			log::info!("in submit_data_unsigned: {:?} from {:?}", payload.n, who);
+
+			<SubmittedData<T>>::insert(&who, payload.n);
 
+			// Close the submission window until `UnsignedInterval` blocks have passed.
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			<NextUnsignedAt<T>>::put(current_block + T::UnsignedInterval::get());
+
+			Self::deposit_event(Event::DataSubmitted { n: payload.n, who });
 			// Return a successful DispatchResultWithPostInfo
 			Ok(())
 		}
+
+		/// Submit data signed by an authority's offchain worker.
+		#[pallet::call_index(3)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn submit_data_signed(origin: OriginFor<T>, n: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::authorities().contains(&who), Error::<T>::NotAuthority);
+
+			log::info!("in submit_data_signed: {:?} from {:?}", n, who);
+
+			// Record which account submitted the data.
+			<SubmittedData<T>>::insert(&who, n);
+
+			Self::deposit_event(Event::DataSubmitted { n, who });
+			Ok(())
+		}
+
+		/// Replace the set of offchain worker authorities.
+		#[pallet::call_index(4)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_authorities(
+			origin: OriginFor<T>,
+			authorities: Vec<T::AccountId>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let bounded =
+				BoundedVec::<T::AccountId, T::MaxAuthorities>::try_from(authorities.clone())
+					.map_err(|_| Error::<T>::TooManyAuthorities)?;
+			<Authorities<T>>::put(bounded);
+
+			Self::deposit_event(Event::AuthoritiesSet { authorities });
+			Ok(())
+		}
+
+		/// Submit a price in cents fetched by an authority's offchain worker.
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).ref_time())]
+		pub fn submit_price(origin: OriginFor<T>, price: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::authorities().contains(&who), Error::<T>::NotAuthority);
+
+			Self::add_price(price);
+
+			Self::deposit_event(Event::NewPrice { price, who });
+			Ok(())
+		}
 	}
 
 	#[pallet::hooks]
@@ -114,15 +357,193 @@ pub mod pallet {
 
 		fn offchain_worker(block_number: T::BlockNumber) {
 			log::info!("Hello World from offchain workers!: {:?}", block_number);
+
+			// Workers for sibling blocks (e.g. on reorgs) may run concurrently, let only one in.
+			let mut lock =
+				StorageLock::<BlockAndTime<frame_system::Pallet<T>>>::with_block_and_time_deadline(
+					LOCK_KEY,
+					LOCK_BLOCK_EXPIRATION,
+					Duration::from_millis(LOCK_TIMEOUT_EXPIRATION),
+				);
+			let _guard = match lock.try_lock() {
+				Ok(guard) => guard,
+				Err(_) => {
+					log::info!("Offchain worker locked, skipping block {:?}", block_number);
+					return
+				},
+			};
+
+			if !Self::should_run(block_number) {
+				log::info!("Offchain worker ran recently, skipping block {:?}", block_number);
+				return
+			}
+
 			let value: u64 = 42;
-			let call = Call::submit_data_unsigned { n: value };
-			_ = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
-				.map_err(|_| {
-					log::error!("Failed in offchain_unsigned_tx");
-				});
+
+			// Only one unsigned submission is accepted per window, don't bother otherwise.
+			if Self::next_unsigned_at() <= block_number {
+				Self::submit_unsigned_with_signed_payload(block_number, value);
+			}
+
+			Self::submit_signed(Call::submit_data_signed { n: value });
+
+			// Fetch the price only when a feed URL is configured.
+			if let Some(url) = Self::price_url() {
+				match Self::fetch_price(&url) {
+					Ok(price) => Self::submit_signed(Call::submit_price { price }),
+					Err(e) => log::error!("Failed to fetch price: {:?}", e),
+				}
+			}
 
 			log::info!("Leave from offchain workers!: {:?}", block_number);
 		}
+
+		fn integrity_test() {
+			assert!(T::MaxPrices::get() > 0, "MaxPrices must be at least 1");
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Set `who`'s value, pushing the previous one into its history.
+		fn store_something(who: &T::AccountId, something: u32) {
+			if let Some(old) = <Something<T>>::get(who) {
+				<History<T>>::mutate(who, |history| {
+					// Drops the oldest value once full; only fails if `MaxHistory` is zero.
+					let _ = history.force_insert_keep_right(history.len(), old);
+				});
+			}
+			<Something<T>>::insert(who, something);
+		}
+
+		/// Record `block_number` as the last run if at least `OffchainInterval` blocks have
+		/// passed since the previous one.
+		///
+		/// Runs for an older block than the recorded one (after a reorg) are skipped as well.
+		fn should_run(block_number: T::BlockNumber) -> bool {
+			// Returned while still within the interval.
+			enum Error {
+				RecentlyRun,
+			}
+
+			let last_run = StorageValueRef::persistent(LAST_RUN_KEY);
+			let res = last_run.mutate(
+				|last: Result<Option<T::BlockNumber>, StorageRetrievalError>| match last {
+					Ok(Some(last)) if block_number < last + T::OffchainInterval::get() =>
+						Err(Error::RecentlyRun),
+					_ => Ok(block_number),
+				},
+			);
+
+			match res {
+				Ok(_) => true,
+				Err(MutateStorageError::ValueFunctionFailed(Error::RecentlyRun)) => false,
+				// Another worker updated the value concurrently.
+				Err(MutateStorageError::ConcurrentModification(_)) => false,
+			}
+		}
+
+		/// Submit `call` with a signed transaction from every local key of type [`KEY_TYPE`].
+		fn submit_signed(call: Call<T>) {
+			let signer = Signer::<T, T::AuthorityId>::all_accounts();
+			if !signer.can_sign() {
+				log::error!(
+					"No local accounts available. Consider adding one via `author_insertKey` RPC."
+				);
+				return
+			}
+
+			let results = signer.send_signed_transaction(|_account| call.clone());
+			for (acc, res) in &results {
+				match res {
+					Ok(()) => log::info!("[{:?}] Submitted {:?}", acc.id, call),
+					Err(e) => log::error!("[{:?}] Failed to submit transaction: {:?}", acc.id, e),
+				}
+			}
+		}
+
+		/// The price feed URL from offchain persistent storage, if configured.
+		fn price_url() -> Option<Vec<u8>> {
+			StorageValueRef::persistent(PRICE_URL_KEY).get::<Vec<u8>>().ok().flatten()
+		}
+
+		/// Fetch the current price in cents from `url`.
+		fn fetch_price(url: &[u8]) -> Result<u32, http::Error> {
+			let url = sp_std::str::from_utf8(url).map_err(|_| http::Error::Unknown)?;
+
+			// Don't let a slow endpoint hold up the offchain worker.
+			let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(2_000));
+			let pending = http::Request::get(url)
+				.deadline(deadline)
+				.send()
+				.map_err(|_| http::Error::IoError)?;
+			let response =
+				pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+			if response.code != 200 {
+				log::warn!("Unexpected status code: {}", response.code);
+				return Err(http::Error::Unknown)
+			}
+
+			let body = response.body().collect::<Vec<u8>>();
+			let body_str = sp_std::str::from_utf8(&body).map_err(|_| {
+				log::warn!("No UTF8 body");
+				http::Error::Unknown
+			})?;
+
+			let price = Self::parse_price(body_str).ok_or_else(|| {
+				log::warn!("Unable to extract price from the response: {:?}", body_str);
+				http::Error::Unknown
+			})?;
+
+			log::info!("Got price: {} cents", price);
+			Ok(price)
+		}
+
+		/// Parse the `USD` field of a JSON object into cents.
+		pub fn parse_price(price_str: &str) -> Option<u32> {
+			let val = lite_json::parse_json(price_str);
+			let price = match val.ok()? {
+				JsonValue::Object(obj) => {
+					let (_, v) =
+						obj.into_iter().find(|(k, _)| k.iter().copied().eq("USD".chars()))?;
+					match v {
+						JsonValue::Number(number) => number,
+						_ => return None,
+					}
+				},
+				_ => return None,
+			};
+			if price.negative {
+				return None
+			}
+
+			// Keep two decimal places, dropping any further precision.
+			let cents = match price.fraction_length {
+				0 => 0,
+				1 => price.fraction * 10,
+				len => price.fraction / 10_u64.checked_pow(len - 2)?,
+			};
+			let integer = u32::try_from(price.integer).ok()?;
+			integer.checked_mul(100)?.checked_add(cents as u32)
+		}
+
+		/// Append `price`, dropping the oldest one once `MaxPrices` are stored.
+		fn add_price(price: u32) {
+			<Prices<T>>::mutate(|prices| {
+				// Only fails if `MaxPrices` is zero, which `integrity_test` rules out.
+				let _ = prices.force_insert_keep_right(prices.len(), price);
+			});
+		}
+
+		/// Average of the stored prices, `None` before the first submission.
+		pub fn average_price() -> Option<u32> {
+			let prices = Self::prices();
+			if prices.is_empty() {
+				None
+			} else {
+				let sum = prices.iter().fold(0_u64, |sum, price| sum + *price as u64);
+				Some((sum / prices.len() as u64) as u32)
+			}
+		}
 	}
 
 	#[pallet::validate_unsigned]
@@ -131,17 +552,60 @@ pub mod pallet {
 
 		// 参考：https://github.com/paritytech/substrate/blob/polkadot-v0.9.35/frame/examples/offchain-worker/src/lib.rs
 		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-			if let Call::submit_data_unsigned { n: _ } = call {
-				//let provide = b"submit_xxx_unsigned".to_vec();
-				ValidTransaction::with_tag_prefix("ExampleOffchainWorker")
-					.priority(10000)
-					.and_provides(1)
-					.longevity(3)
-					.propagate(true)
-					.build()
+			if let Call::submit_data_unsigned { payload, signature } = call {
+				let signature_valid =
+					SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
+				if !signature_valid {
+					return InvalidTransaction::BadProof.into()
+				}
+				Self::validate_transaction_parameters(&payload.block_number, &payload.public)
 			} else {
 				InvalidTransaction::Call.into()
 			}
 		}
 	}
+
+	impl<T: Config> Pallet<T> {
+		/// Submit `n` with an unsigned transaction carrying a payload signed by any local key.
+		fn submit_unsigned_with_signed_payload(block_number: T::BlockNumber, n: u64) {
+			let result = Signer::<T, T::AuthorityId>::any_account().send_unsigned_transaction(
+				|account| DataPayload { block_number, n, public: account.public.clone() },
+				|payload, signature| Call::submit_data_unsigned { payload, signature },
+			);
+
+			match result {
+				Some((_, Ok(()))) => log::info!("Submitted unsigned data: {}", n),
+				Some((_, Err(()))) => log::error!("Failed in offchain_unsigned_tx"),
+				None => log::error!("No local account available to sign the unsigned payload"),
+			}
+		}
+
+		fn validate_transaction_parameters(
+			block_number: &T::BlockNumber,
+			public: &T::Public,
+		) -> TransactionValidity {
+			// Reject submissions for a window that has already been used.
+			let next_unsigned_at = <NextUnsignedAt<T>>::get();
+			if &next_unsigned_at > block_number {
+				return InvalidTransaction::Stale.into()
+			}
+			// Reject submissions from the future.
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			if &current_block < block_number {
+				return InvalidTransaction::Future.into()
+			}
+			// Only configured authorities may submit.
+			if !Self::authorities().contains(&public.clone().into_account()) {
+				return InvalidTransaction::BadSigner.into()
+			}
+
+			ValidTransaction::with_tag_prefix("TemplateOffchainWorker")
+				.priority(T::UnsignedPriority::get())
+				// One submission per window: transactions for the same window replace each other.
+				.and_provides(next_unsigned_at)
+				.longevity(5)
+				.propagate(true)
+				.build()
+		}
+	}
 }
@@ -0,0 +1,98 @@
+//! Storage migrations for the template pallet.
+
+/// Migrate `Something` from a single global value to a value per account.
+pub mod v1 {
+	use crate::{Config, Pallet, Something};
+	use frame_support::{
+		pallet_prelude::*,
+		storage_alias,
+		traits::{GetStorageVersion, OnRuntimeUpgrade},
+	};
+	use sp_runtime::traits::TrailingZeroInput;
+	use sp_std::marker::PhantomData;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::prelude::*;
+
+	/// The storage layout before v1.
+	pub mod v0 {
+		use super::*;
+
+		/// The single value shared by every signer.
+		#[storage_alias]
+		pub type Something<T: Config> = StorageValue<Pallet<T>, u32>;
+	}
+
+	/// The account that keeps the old value when `Owner` returns `None`: the all-zero
+	/// account, which nobody holds a key for.
+	///
+	/// The value stays readable with `something(unowned_account())` instead of being lost.
+	pub fn unowned_account<T: Config>() -> T::AccountId {
+		T::AccountId::decode(&mut TrailingZeroInput::zeroes())
+			.expect("infinite length input; no invalid inputs for type; qed")
+	}
+
+	/// Move the old global value to the account returned by `Owner`.
+	///
+	/// Nobody can tell who stored the old value, so the runtime picks the account that keeps
+	/// it. If `Owner` returns `None` the value is kept under [`unowned_account`]. Starts no
+	/// history.
+	pub struct MigrateToV1<T, Owner>(PhantomData<(T, Owner)>);
+
+	impl<T: Config, Owner: Get<Option<T::AccountId>>> OnRuntimeUpgrade for MigrateToV1<T, Owner> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain = Pallet::<T>::on_chain_storage_version();
+			if on_chain != 0 {
+				log::info!("pallet_template: v1 migration skipped, storage is {:?}", on_chain);
+				return T::DbWeight::get().reads(1)
+			}
+
+			// The old value lives under the prefix of the new map, so take it before writing.
+			let old = v0::Something::<T>::take();
+			let mut writes = 2;
+			if let Some(value) = old {
+				let owner = Owner::get().unwrap_or_else(|| {
+					log::warn!("pallet_template: no owner configured, keeping the value unowned");
+					unowned_account::<T>()
+				});
+				Something::<T>::insert(&owner, value);
+				writes += 1;
+				log::info!("pallet_template: moved value {} to {:?}", value, owner);
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2, writes)
+		}
+
+		/// Record the value that should end up with the owner.
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			let on_chain = Pallet::<T>::on_chain_storage_version();
+			ensure!(on_chain <= 1, "pallet_template: unexpected storage version");
+			let expected = if on_chain == 0 {
+				v0::Something::<T>::get()
+					.map(|value| (value, Owner::get().unwrap_or_else(unowned_account::<T>)))
+			} else {
+				None
+			};
+			Ok(expected.encode())
+		}
+
+		/// Check the version was bumped and the owner received the old value.
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let expected: Option<(u32, T::AccountId)> = Decode::decode(&mut &state[..])
+				.map_err(|_| "pallet_template: invalid pre_upgrade state")?;
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"pallet_template: storage version not 1"
+			);
+			if let Some((value, owner)) = expected {
+				ensure!(
+					Something::<T>::get(&owner) == Some(value),
+					"pallet_template: value not moved to the owner"
+				);
+			}
+			Ok(())
+		}
+	}
+}
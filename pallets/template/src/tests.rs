@@ -1,5 +1,58 @@
-use crate::{mock::*, Error, Event};
-use frame_support::{assert_noop, assert_ok};
+use crate::{migrations::v1, mock::*, DataPayload, Error, Event};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GenesisBuild, Hooks, OnRuntimeUpgrade, StorageVersion},
+	unsigned::ValidateUnsigned,
+};
+use frame_system::offchain::SignedPayload;
+use parking_lot::RwLock;
+use sp_core::{
+	offchain::{testing, OffchainWorkerExt, TransactionPoolExt},
+	sr25519, Pair,
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::{
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{BlockAndTime, StorageLock},
+		Duration,
+	},
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
+use std::sync::Arc;
+
+type Payload = DataPayload<sr25519::Public, u64>;
+type OffchainState = Arc<RwLock<testing::OffchainState>>;
+type PoolState = Arc<RwLock<testing::PoolState>>;
+
+fn signed_payload(pair: &sr25519::Pair, block_number: u64) -> (Payload, sr25519::Signature) {
+	let payload = DataPayload { block_number, n: 7, public: pair.public() };
+	let signature = pair.sign(&payload.encode());
+	(payload, signature)
+}
+
+/// `new_test_ext` with the offchain worker, transaction pool and keystore extensions
+/// registered, the keystore holding one `KEY_TYPE` key derived from `seed`.
+fn new_offchain_test_ext(seed: &str) -> (sp_io::TestExternalities, OffchainState, PoolState) {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	SyncCryptoStore::sr25519_generate_new(&keystore, crate::KEY_TYPE, Some(seed)).unwrap();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(Arc::new(keystore)));
+	(t, offchain_state, pool_state)
+}
+
+fn validate(
+	(payload, signature): (Payload, sr25519::Signature),
+) -> sp_runtime::transaction_validity::TransactionValidity {
+	let call = crate::Call::submit_data_unsigned { payload, signature };
+	TemplateModule::validate_unsigned(TransactionSource::External, &call)
+}
 
 #[test]
 fn it_works_for_default_value() {
@@ -7,11 +60,12 @@ fn it_works_for_default_value() {
 		// Go past genesis block so events get deposited
 		System::set_block_number(1);
 		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(account(1)), 42));
 		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::something(account(1)), Some(42));
+		assert_eq!(TemplateModule::something(account(2)), None);
 		// Assert that the correct event was deposited
-		System::assert_last_event(Event::SomethingStored { something: 42, who: 1 }.into());
+		System::assert_last_event(Event::SomethingStored { something: 42, who: account(1) }.into());
 	});
 }
 
@@ -20,8 +74,394 @@ fn correct_error_for_none_value() {
 	new_test_ext().execute_with(|| {
 		// Ensure the expected error is thrown when no value is present.
 		assert_noop!(
-			TemplateModule::cause_error(RuntimeOrigin::signed(1)),
+			TemplateModule::cause_error(RuntimeOrigin::signed(account(1))),
+			Error::<Test>::NoneValue
+		);
+	});
+}
+
+#[test]
+fn cause_error_increments_callers_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(account(1)), 42));
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(account(1))));
+		assert_eq!(TemplateModule::something(account(1)), Some(43));
+		assert_eq!(TemplateModule::history(account(1)).into_inner(), vec![42]);
+
+		// Other accounts have their own value.
+		assert_noop!(
+			TemplateModule::cause_error(RuntimeOrigin::signed(account(2))),
 			Error::<Test>::NoneValue
 		);
+
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(account(2)), u32::MAX));
+		assert_noop!(
+			TemplateModule::cause_error(RuntimeOrigin::signed(account(2))),
+			Error::<Test>::StorageOverflow
+		);
+	});
+}
+
+#[test]
+fn history_keeps_most_recent_values() {
+	new_test_ext().execute_with(|| {
+		for value in 1..=5 {
+			assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(account(1)), value));
+		}
+
+		// `MaxHistory` is 3, the oldest values were dropped.
+		assert_eq!(TemplateModule::something(account(1)), Some(5));
+		assert_eq!(TemplateModule::history(account(1)).into_inner(), vec![2, 3, 4]);
+		assert!(TemplateModule::history(account(2)).is_empty());
+	});
+}
+
+#[test]
+fn genesis_config_sets_values() {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	crate::GenesisConfig::<Test> {
+		authorities: vec![],
+		something: vec![(account(1), 7), (account(2), 9)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		assert_eq!(TemplateModule::something(account(1)), Some(7));
+		assert_eq!(TemplateModule::something(account(2)), Some(9));
+		assert!(TemplateModule::history(account(1)).is_empty());
+	});
+}
+
+frame_support::parameter_types! {
+	pub MigrationOwner: Option<AccountId> = Some(account(1));
+	pub const NoOwner: Option<AccountId> = None;
+}
+
+#[test]
+fn migration_to_v1_moves_value_to_owner() {
+	new_test_ext().execute_with(|| {
+		v1::v0::Something::<Test>::put(42);
+		assert_eq!(StorageVersion::get::<TemplateModule>(), 0);
+
+		v1::MigrateToV1::<Test, MigrationOwner>::on_runtime_upgrade();
+
+		assert_eq!(v1::v0::Something::<Test>::get(), None);
+		assert_eq!(TemplateModule::something(account(1)), Some(42));
+		assert_eq!(crate::Something::<Test>::iter().count(), 1);
+		assert_eq!(StorageVersion::get::<TemplateModule>(), 1);
+
+		// Running again does nothing.
+		v1::v0::Something::<Test>::put(7);
+		v1::MigrateToV1::<Test, MigrationOwner>::on_runtime_upgrade();
+		assert_eq!(TemplateModule::something(account(1)), Some(42));
+	});
+}
+
+#[test]
+fn migration_to_v1_keeps_value_without_owner() {
+	new_test_ext().execute_with(|| {
+		v1::v0::Something::<Test>::put(42);
+
+		v1::MigrateToV1::<Test, NoOwner>::on_runtime_upgrade();
+
+		assert_eq!(v1::v0::Something::<Test>::get(), None);
+		assert_eq!(TemplateModule::something(v1::unowned_account::<Test>()), Some(42));
+		assert_eq!(crate::Something::<Test>::iter().count(), 1);
+		assert_eq!(StorageVersion::get::<TemplateModule>(), 1);
+	});
+}
+
+#[test]
+fn submit_data_signed_records_submitter() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::set_authorities(RuntimeOrigin::root(), vec![account(2)]));
+		assert_ok!(TemplateModule::submit_data_signed(RuntimeOrigin::signed(account(2)), 7));
+		assert_eq!(TemplateModule::submitted_data(account(2)), Some(7));
+		System::assert_last_event(Event::DataSubmitted { n: 7, who: account(2) }.into());
+	});
+}
+
+#[test]
+fn submit_data_signed_rejects_non_authority() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_authorities(RuntimeOrigin::root(), vec![account(1)]));
+
+		assert_noop!(
+			TemplateModule::submit_data_signed(RuntimeOrigin::signed(account(2)), 7),
+			Error::<Test>::NotAuthority
+		);
+		assert_eq!(TemplateModule::submitted_data(account(2)), None);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_signed_transaction() {
+	const PHRASE: &str =
+		"news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+
+	let (mut t, _offchain_state, pool_state) =
+		new_offchain_test_ext(&format!("{}/hunter1", PHRASE));
+
+	t.execute_with(|| {
+		let public_key = sp_io::crypto::sr25519_public_keys(crate::KEY_TYPE)[0];
+		TemplateModule::offchain_worker(1);
+
+		// One unsigned and one signed transaction are submitted.
+		let txs = pool_state.read().transactions.clone();
+		assert_eq!(txs.len(), 2);
+
+		// The unsigned transaction carries a payload signed by the local key.
+		let unsigned = Extrinsic::decode(&mut &*txs[0]).unwrap();
+		assert_eq!(unsigned.signature, None);
+		if let RuntimeCall::TemplateModule(crate::Call::submit_data_unsigned {
+			payload,
+			signature,
+		}) = unsigned.call
+		{
+			assert_eq!(payload, DataPayload { block_number: 1, n: 42, public: public_key });
+			assert!(SignedPayload::<Test>::verify::<crate::crypto::TemplateAuthId>(
+				&payload, signature
+			));
+		} else {
+			panic!("unexpected call: {:?}", unsigned.call);
+		}
+
+		let signed = Extrinsic::decode(&mut &*txs[1]).unwrap();
+		assert_eq!(signed.signature, Some((0, ())));
+		assert_eq!(
+			signed.call,
+			RuntimeCall::TemplateModule(crate::Call::submit_data_signed { n: 42 })
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_unsigned_in_closed_window() {
+	let (mut t, _offchain_state, pool_state) = new_offchain_test_ext("//Alice");
+
+	t.execute_with(|| {
+		crate::NextUnsignedAt::<Test>::put(5);
+		TemplateModule::offchain_worker(4);
+
+		// Only the signed transaction is submitted.
+		let txs = pool_state.read().transactions.clone();
+		assert_eq!(txs.len(), 1);
+		assert!(Extrinsic::decode(&mut &*txs[0]).unwrap().signature.is_some());
+	});
+}
+
+#[test]
+fn unsigned_payload_from_authority_is_accepted() {
+	new_test_ext().execute_with(|| {
+		let alice = sr25519::Pair::from_string("//Alice", None).unwrap();
+		assert_ok!(TemplateModule::set_authorities(RuntimeOrigin::root(), vec![alice.public()]));
+		System::set_block_number(3);
+
+		let valid = validate(signed_payload(&alice, 3)).unwrap();
+		assert_eq!(valid.priority, 100);
+		assert_eq!(valid.longevity, 5);
+
+		// Dispatch records the submitter and closes the window for `UnsignedInterval` blocks.
+		let (payload, signature) = signed_payload(&alice, 3);
+		assert_ok!(TemplateModule::submit_data_unsigned(RuntimeOrigin::none(), payload, signature));
+		assert_eq!(TemplateModule::submitted_data(alice.public()), Some(7));
+		assert_eq!(TemplateModule::next_unsigned_at(), 8);
+		System::assert_last_event(Event::DataSubmitted { n: 7, who: alice.public() }.into());
+
+		// A payload for the closed window is stale, the next window is accepted again.
+		assert_eq!(validate(signed_payload(&alice, 3)), Err(InvalidTransaction::Stale.into()));
+		System::set_block_number(8);
+		assert!(validate(signed_payload(&alice, 8)).is_ok());
+	});
+}
+
+#[test]
+fn invalid_unsigned_payloads_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let alice = sr25519::Pair::from_string("//Alice", None).unwrap();
+		let bob = sr25519::Pair::from_string("//Bob", None).unwrap();
+		assert_ok!(TemplateModule::set_authorities(RuntimeOrigin::root(), vec![alice.public()]));
+		System::set_block_number(3);
+
+		// Payload claims to be from Alice but is signed by Bob.
+		let payload = DataPayload { block_number: 3, n: 7, public: alice.public() };
+		let signature = bob.sign(&payload.encode());
+		assert_eq!(validate((payload, signature)), Err(InvalidTransaction::BadProof.into()));
+
+		// Correctly signed by Bob, who is not an authority.
+		assert_eq!(validate(signed_payload(&bob, 3)), Err(InvalidTransaction::BadSigner.into()));
+
+		// Payload for a future block.
+		assert_eq!(validate(signed_payload(&alice, 4)), Err(InvalidTransaction::Future.into()));
+
+		// Unsigned submissions are only accepted through `validate_unsigned`.
+		let (payload, signature) = signed_payload(&alice, 3);
+		assert_noop!(
+			TemplateModule::submit_data_unsigned(
+				RuntimeOrigin::signed(account(1)),
+				payload,
+				signature
+			),
+			sp_runtime::traits::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn set_authorities_requires_root_and_bound() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::set_authorities(RuntimeOrigin::signed(account(1)), vec![account(1)]),
+			sp_runtime::traits::BadOrigin
+		);
+		assert_noop!(
+			TemplateModule::set_authorities(RuntimeOrigin::root(), (1..=5).map(account).collect()),
+			Error::<Test>::TooManyAuthorities
+		);
+		assert_ok!(TemplateModule::set_authorities(RuntimeOrigin::root(), vec![account(1)]));
+		assert_eq!(TemplateModule::authorities().into_inner(), vec![account(1)]);
+	});
+}
+
+#[test]
+fn parse_price_works() {
+	let test_data = vec![
+		("{\"USD\":6536.92}", Some(653692)),
+		("{\"USD\":65.92}", Some(6592)),
+		("{\"USD\":6536.924565}", Some(653692)),
+		("{\"USD\":6536}", Some(653600)),
+		("{\"USD\":6536.9}", Some(653690)),
+		("{\"USD2\":6536}", None),
+		("{\"USD\":-6536}", None),
+		("{\"USD\":\"6432\"}", None),
+		("not json", None),
+	];
+
+	for (json, expected) in test_data {
+		assert_eq!(TemplateModule::parse_price(json), expected);
+	}
+}
+
+#[test]
+fn submit_price_keeps_bounded_moving_average() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(TemplateModule::average_price(), None);
+
+		// Only authorities may submit prices.
+		assert_noop!(
+			TemplateModule::submit_price(RuntimeOrigin::signed(account(1)), 100),
+			Error::<Test>::NotAuthority
+		);
+		assert_ok!(TemplateModule::set_authorities(RuntimeOrigin::root(), vec![account(1)]));
+
+		assert_ok!(TemplateModule::submit_price(RuntimeOrigin::signed(account(1)), 100));
+		assert_ok!(TemplateModule::submit_price(RuntimeOrigin::signed(account(1)), 200));
+		assert_eq!(TemplateModule::average_price(), Some(150));
+		System::assert_last_event(Event::NewPrice { price: 200, who: account(1) }.into());
+
+		// Only the last `MaxPrices` (3) prices are kept.
+		assert_ok!(TemplateModule::submit_price(RuntimeOrigin::signed(account(1)), 300));
+		assert_ok!(TemplateModule::submit_price(RuntimeOrigin::signed(account(1)), 700));
+		assert_eq!(TemplateModule::prices().into_inner(), vec![200, 300, 700]);
+		assert_eq!(TemplateModule::average_price(), Some(400));
+	});
+}
+
+#[test]
+fn offchain_worker_fetches_and_submits_price() {
+	let (mut t, offchain_state, pool_state) = new_offchain_test_ext("//Alice");
+
+	offchain_state.write().expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: "https://example.com/price".into(),
+		response: Some(br#"{"USD": 155.23}"#.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+
+	t.execute_with(|| {
+		StorageValueRef::persistent(crate::PRICE_URL_KEY)
+			.set(&b"https://example.com/price".to_vec());
+
+		TemplateModule::offchain_worker(1);
+
+		// Unsigned and signed data submissions, then the signed price submission.
+		let txs = pool_state.read().transactions.clone();
+		assert_eq!(txs.len(), 3);
+		let tx = Extrinsic::decode(&mut &*txs[2]).unwrap();
+		assert_eq!(tx.signature, Some((0, ())));
+		assert_eq!(
+			tx.call,
+			RuntimeCall::TemplateModule(crate::Call::submit_price { price: 15523 })
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_price_without_url() {
+	let (mut t, _offchain_state, pool_state) = new_offchain_test_ext("//Alice");
+
+	t.execute_with(|| {
+		TemplateModule::offchain_worker(1);
+
+		// No HTTP request was expected, so none was made, and no price was submitted.
+		let txs = pool_state.read().transactions.clone();
+		assert!(txs.iter().all(|tx| !matches!(
+			Extrinsic::decode(&mut &**tx).unwrap().call,
+			RuntimeCall::TemplateModule(crate::Call::submit_price { .. })
+		)));
+	});
+}
+
+#[test]
+fn offchain_worker_respects_interval() {
+	let (mut t, _offchain_state, pool_state) = new_offchain_test_ext("//Alice");
+
+	t.execute_with(|| {
+		// The first run submits an unsigned and a signed transaction.
+		TemplateModule::offchain_worker(1);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		// Runs within `OffchainInterval` (3) blocks, including for the same block, are skipped.
+		TemplateModule::offchain_worker(1);
+		TemplateModule::offchain_worker(3);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		TemplateModule::offchain_worker(4);
+		assert_eq!(pool_state.read().transactions.len(), 4);
+
+		// A run for an older block, e.g. after a reorg, is skipped too.
+		TemplateModule::offchain_worker(2);
+		assert_eq!(pool_state.read().transactions.len(), 4);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_while_locked() {
+	let (mut t, _offchain_state, pool_state) = new_offchain_test_ext("//Alice");
+
+	t.execute_with(|| {
+		System::set_block_number(1);
+
+		// Another worker holds the lock.
+		let mut lock = StorageLock::<BlockAndTime<System>>::with_block_and_time_deadline(
+			crate::LOCK_KEY,
+			3,
+			Duration::from_millis(3_000),
+		);
+		let guard = lock.try_lock().unwrap();
+
+		TemplateModule::offchain_worker(1);
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(StorageValueRef::persistent(crate::LAST_RUN_KEY).get::<u64>(), Ok(None));
+
+		// Once released, the worker runs.
+		drop(guard);
+		TemplateModule::offchain_worker(1);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+		assert_eq!(StorageValueRef::persistent(crate::LAST_RUN_KEY).get::<u64>(), Ok(Some(1)));
 	});
 }
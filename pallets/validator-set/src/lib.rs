@@ -0,0 +1,230 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// 管理PoA验证人集合：通过pallet_session在两个session之后生效，Aura/GRANDPA的authority随之更新
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+use frame_support::{pallet_prelude::Weight, traits::Get};
+use sp_runtime::{traits::Convert, Perbill};
+use sp_staking::{
+	offence::{OffenceDetails, OnOffenceHandler},
+	SessionIndex,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_session::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		// 允许添加、移除验证人的origin, 如root或议会
+		type AddRemoveOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		// 验证人数量下限, 防止移除后无人出块
+		#[pallet::constant]
+		type MinAuthorities: Get<u32>;
+		// 验证人数量上限, 应与Aura/GRANDPA的MaxAuthorities保持一致
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+	}
+
+	// 当前的验证人集合
+	// pallet_session在session N结束时规划session N+2的验证人, 在session N中的修改于session N+2生效
+	#[pallet::storage]
+	#[pallet::getter(fn validators)]
+	pub type Validators<T: Config> =
+		StorageValue<_, BoundedVec<T::ValidatorId, T::MaxValidators>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub initial_validators: Vec<T::ValidatorId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { initial_validators: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			// 创世验证人需在pallet_session的创世配置之前写入, 由new_session_genesis返回
+			assert!(
+				self.initial_validators.len() as u32 >= T::MinAuthorities::get(),
+				"too few genesis validators"
+			);
+			let validators = BoundedVec::<T::ValidatorId, T::MaxValidators>::try_from(
+				self.initial_validators.clone(),
+			)
+			.expect("too many genesis validators");
+			<Validators<T>>::put(validators);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		// 添加验证人, 在当前session之后的第二个session生效
+		ValidatorAdded { validator: T::ValidatorId },
+		// 移除验证人, 在当前session之后的第二个session生效
+		ValidatorRemoved { validator: T::ValidatorId },
+		// 作恶(如GRANDPA重复投票)的验证人被移除, 当前session立即禁用, 在当前session之后的第二个session移出
+		OffenderRemoved { validator: T::ValidatorId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		// 已经是验证人
+		DuplicateValidator,
+		// 不是验证人
+		NotValidator,
+		// 移除后验证人数量低于下限
+		TooFewValidators,
+		// 验证人数量超过上限
+		TooManyValidators,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		// 添加验证人, 验证人需先通过session.set_keys设置session key才会实际出块
+		// 下一次session轮换时排入队列, 在当前session之后的第二个session开始出块
+		#[pallet::call_index(0)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn add_validator(origin: OriginFor<T>, validator: T::ValidatorId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			<Validators<T>>::try_mutate(|validators| -> DispatchResult {
+				ensure!(!validators.contains(&validator), Error::<T>::DuplicateValidator);
+				validators
+					.try_push(validator.clone())
+					.map_err(|_| Error::<T>::TooManyValidators)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ValidatorAdded { validator });
+
+			Ok(())
+		}
+
+		// 移除验证人, 移除后的数量不能低于MinAuthorities
+		// 与添加相同, 在当前session之后的第二个session才停止出块
+		#[pallet::call_index(1)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1).ref_time())]
+		pub fn remove_validator(origin: OriginFor<T>, validator: T::ValidatorId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			<Validators<T>>::try_mutate(|validators| -> DispatchResult {
+				let index = validators
+					.iter()
+					.position(|v| v == &validator)
+					.ok_or(Error::<T>::NotValidator)?;
+				ensure!(
+					validators.len() as u32 > T::MinAuthorities::get(),
+					Error::<T>::TooFewValidators
+				);
+				validators.remove(index);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ValidatorRemoved { validator });
+
+			Ok(())
+		}
+	}
+}
+
+// pallet_session在每次轮换时向本模块询问所规划的session(新session之后的下一个)的验证人
+impl<T: Config> pallet_session::SessionManager<T::ValidatorId> for Pallet<T> {
+	fn new_session(new_index: u32) -> Option<Vec<T::ValidatorId>> {
+		log::info!("pallet_validator_set: planning validators for session {}", new_index);
+		Some(Self::validators().into_inner())
+	}
+
+	fn end_session(_end_index: u32) {}
+
+	fn start_session(_start_index: u32) {}
+}
+
+// 配合pallet_session::historical使用, 验证人的完整身份(FullIdentification)即ValidatorId本身
+impl<T: Config> pallet_session::historical::SessionManager<T::ValidatorId, T::ValidatorId>
+	for Pallet<T>
+{
+	fn new_session(new_index: u32) -> Option<Vec<(T::ValidatorId, T::ValidatorId)>> {
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v.clone(), v)).collect())
+	}
+
+	fn end_session(end_index: u32) {
+		<Self as pallet_session::SessionManager<_>>::end_session(end_index)
+	}
+
+	fn start_session(start_index: u32) {
+		<Self as pallet_session::SessionManager<_>>::start_session(start_index)
+	}
+}
+
+// pallet_offences确认作恶后的处理：PoA没有质押可罚没, 在当前session禁用作恶者并将其移出验证人集合
+// 若移除后低于MinAuthorities, 只禁用不移除
+impl<T: Config> OnOffenceHandler<T::AccountId, (T::ValidatorId, T::ValidatorId), Weight>
+	for Pallet<T>
+{
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, (T::ValidatorId, T::ValidatorId)>],
+		_slash_fraction: &[Perbill],
+		_session: SessionIndex,
+	) -> Weight {
+		let mut weight = Weight::zero();
+
+		for details in offenders {
+			let (validator, _) = &details.offender;
+
+			pallet_session::Pallet::<T>::disable(validator);
+
+			let removed = <Validators<T>>::mutate(|validators| {
+				match validators.iter().position(|v| v == validator) {
+					Some(index) if validators.len() as u32 > T::MinAuthorities::get() => {
+						validators.remove(index);
+						true
+					},
+					_ => false,
+				}
+			});
+
+			if removed {
+				Self::deposit_event(Event::OffenderRemoved { validator: validator.clone() });
+			} else {
+				log::warn!("pallet_validator_set: offender {:?} disabled but kept", validator);
+			}
+
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 3));
+		}
+
+		weight
+	}
+}
+
+// 验证人的ValidatorId即账户本身, 供pallet_session的ValidatorIdOf使用
+pub struct ValidatorOf<T>(PhantomData<T>);
+
+impl<T: Config> Convert<T::ValidatorId, Option<T::ValidatorId>> for ValidatorOf<T> {
+	fn convert(account: T::ValidatorId) -> Option<T::ValidatorId> {
+		Some(account)
+	}
+}
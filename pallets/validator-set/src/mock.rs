@@ -0,0 +1,164 @@
+use crate as pallet_validator_set;
+use frame_support::traits::{
+	ConstU16, ConstU32, ConstU64, GenesisBuild, Hooks, KeyOwnerProofSystem,
+};
+use frame_system as system;
+use frame_system::EnsureRoot;
+use sp_core::{ed25519, Pair, H256};
+use sp_finality_grandpa::AuthorityId as GrandpaId;
+use sp_runtime::{
+	impl_opaque_keys,
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, IdentityLookup, OpaqueKeys},
+	KeyTypeId,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// 验证人集合需在Session之前, 创世时Session从ValidatorSet读取初始验证人
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Authorship: pallet_authorship,
+		ValidatorSet: pallet_validator_set,
+		Session: pallet_session,
+		Historical: pallet_session::historical,
+		Offences: pallet_offences,
+		Grandpa: pallet_grandpa,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = TestXt<RuntimeCall, ()>;
+}
+
+impl_opaque_keys! {
+	pub struct MockSessionKeys {
+		pub grandpa: Grandpa,
+	}
+}
+
+// 每5个区块轮换一次session
+pub type Period = ConstU64<5>;
+pub type Offset = ConstU64<0>;
+
+impl pallet_session::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = u64;
+	type ValidatorIdOf = pallet_validator_set::ValidatorOf<Self>;
+	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+	type SessionHandler = <MockSessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = MockSessionKeys;
+	type WeightInfo = ();
+}
+
+impl pallet_session::historical::Config for Test {
+	type FullIdentification = u64;
+	type FullIdentificationOf = pallet_validator_set::ValidatorOf<Self>;
+}
+
+impl pallet_authorship::Config for Test {
+	type FindAuthor = ();
+	type UncleGenerations = ConstU64<0>;
+	type FilterUncle = ();
+	type EventHandler = ();
+}
+
+impl pallet_offences::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = ValidatorSet;
+}
+
+impl pallet_grandpa::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type KeyOwnerProofSystem = Historical;
+	type KeyOwnerProof =
+		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
+	type KeyOwnerIdentification = <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(
+		KeyTypeId,
+		GrandpaId,
+	)>>::IdentificationTuple;
+	type HandleEquivocation =
+		pallet_grandpa::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ConstU64<100>>;
+	type WeightInfo = ();
+	type MaxAuthorities = ConstU32<4>;
+}
+
+impl pallet_validator_set::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type AddRemoveOrigin = EnsureRoot<u64>;
+	type MinAuthorities = ConstU32<2>;
+	type MaxValidators = ConstU32<4>;
+}
+
+// 账户id对应的GRANDPA密钥
+pub fn grandpa_pair(id: u64) -> ed25519::Pair {
+	ed25519::Pair::from_seed(&[id as u8; 32])
+}
+
+pub fn session_keys(id: u64) -> MockSessionKeys {
+	MockSessionKeys { grandpa: grandpa_pair(id).public().into() }
+}
+
+// 创世验证人为1、2、3, 账户1到5都设置了session key
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_validator_set::GenesisConfig::<Test> { initial_validators: vec![1, 2, 3] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	pallet_session::GenesisConfig::<Test> {
+		keys: (1..=5).map(|id| (id, id, session_keys(id))).collect(),
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+// 推进到下一个session的起始区块
+pub fn next_session() {
+	let next = (System::block_number() / 5 + 1) * 5;
+	System::set_block_number(next);
+	Session::on_initialize(next);
+}
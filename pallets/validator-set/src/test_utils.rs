@@ -0,0 +1,30 @@
+// 测试工具: 供本pallet的mock及runtime的测试共用, 通过test-utils特性导出
+use codec::Encode;
+use sp_core::{ed25519, Pair};
+use sp_finality_grandpa::{Equivocation, EquivocationProof, RoundNumber, SetId};
+
+// 构造一个验证人在同一轮对两个不同区块投prevote的作恶证明
+pub fn equivocation_proof<H: Clone + Encode, N: Clone + Encode>(
+	set_id: SetId,
+	round: RoundNumber,
+	pair: &ed25519::Pair,
+	first: (H, N),
+	second: (H, N),
+) -> EquivocationProof<H, N> {
+	let signed_prevote = |(target_hash, target_number): (H, N)| {
+		let prevote = finality_grandpa::Prevote { target_hash, target_number };
+		let message = finality_grandpa::Message::Prevote(prevote.clone());
+		let payload = sp_finality_grandpa::localized_payload(round, set_id, &message);
+		(prevote, pair.sign(&payload).into())
+	};
+
+	EquivocationProof::new(
+		set_id,
+		Equivocation::Prevote(finality_grandpa::Equivocation {
+			round_number: round,
+			identity: pair.public().into(),
+			first: signed_prevote(first),
+			second: signed_prevote(second),
+		}),
+	)
+}
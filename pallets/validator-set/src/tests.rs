@@ -0,0 +1,184 @@
+use crate::{mock::*, test_utils::equivocation_proof, Error, Event};
+use frame_support::{assert_noop, assert_ok, traits::KeyOwnerProofSystem};
+use sp_core::{Pair, H256};
+use sp_runtime::traits::BadOrigin;
+
+/// 创世验证人由本模块提供给Session
+#[test]
+fn genesis_validators_work() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 2, 3]);
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+	})
+}
+
+/// 添加验证人, 在下一个session排队, 再下一个session生效
+#[test]
+fn add_validator_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), 4));
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 2, 3, 4]);
+		System::assert_last_event(Event::ValidatorAdded { validator: 4 }.into());
+
+		// 当前session的验证人不变
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+
+		next_session();
+		let queued: Vec<u64> = Session::queued_keys().into_iter().map(|(v, _)| v).collect();
+		assert_eq!(queued, vec![1, 2, 3, 4]);
+
+		next_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3, 4]);
+	})
+}
+
+/// 移除验证人
+#[test]
+fn remove_validator_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), 2));
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 3]);
+		System::assert_last_event(Event::ValidatorRemoved { validator: 2 }.into());
+
+		next_session();
+		next_session();
+		assert_eq!(Session::validators(), vec![1, 3]);
+	})
+}
+
+/// 只有AddRemoveOrigin可以添加、移除验证人
+#[test]
+fn add_remove_validator_requires_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(RuntimeOrigin::signed(1), 4), BadOrigin);
+		assert_noop!(ValidatorSet::remove_validator(RuntimeOrigin::signed(1), 2), BadOrigin);
+	})
+}
+
+/// 添加验证人失败 - 已是验证人或超过上限
+#[test]
+fn add_validator_failed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), 1),
+			Error::<Test>::DuplicateValidator
+		);
+
+		assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), 4));
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), 5),
+			Error::<Test>::TooManyValidators
+		);
+	})
+}
+
+/// 移除验证人失败 - 不是验证人或低于下限
+#[test]
+fn remove_validator_failed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), 4),
+			Error::<Test>::NotValidator
+		);
+
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), 3));
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), 2),
+			Error::<Test>::TooFewValidators
+		);
+	})
+}
+
+/// 报告GRANDPA重复投票, 作恶的验证人被禁用并移出验证人集合
+#[test]
+fn report_grandpa_equivocation_removes_offender() {
+	new_test_ext().execute_with(|| {
+		let set_id = Grandpa::current_set_id();
+		let offender = grandpa_pair(2);
+
+		// 验证人2在同一轮对两个不同区块投票
+		let proof = equivocation_proof(
+			set_id,
+			1,
+			&offender,
+			(H256::repeat_byte(1), 1),
+			(H256::repeat_byte(2), 1),
+		);
+		let key_owner_proof =
+			Historical::prove((sp_finality_grandpa::KEY_TYPE, offender.public().into())).unwrap();
+
+		assert_ok!(Grandpa::report_equivocation_unsigned(
+			RuntimeOrigin::none(),
+			Box::new(proof),
+			key_owner_proof,
+		));
+
+		System::assert_has_event(Event::OffenderRemoved { validator: 2 }.into());
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 3]);
+		// 验证人2在当前session的索引为1, 立即被禁用
+		assert_eq!(Session::disabled_validators(), vec![1]);
+
+		next_session();
+		next_session();
+		assert_eq!(Session::validators(), vec![1, 3]);
+	})
+}
+
+/// 作恶的验证人移除后低于下限时只禁用不移除
+#[test]
+fn offender_kept_at_min_authorities() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), 3));
+
+		let set_id = Grandpa::current_set_id();
+		let offender = grandpa_pair(2);
+		let proof = equivocation_proof(
+			set_id,
+			1,
+			&offender,
+			(H256::repeat_byte(1), 1),
+			(H256::repeat_byte(2), 1),
+		);
+		let key_owner_proof =
+			Historical::prove((sp_finality_grandpa::KEY_TYPE, offender.public().into())).unwrap();
+
+		assert_ok!(Grandpa::report_equivocation_unsigned(
+			RuntimeOrigin::none(),
+			Box::new(proof),
+			key_owner_proof,
+		));
+
+		assert_eq!(ValidatorSet::validators().into_inner(), vec![1, 2]);
+		assert_eq!(Session::disabled_validators(), vec![1]);
+	})
+}
+
+/// 同一作恶证明不能重复报告
+#[test]
+fn duplicate_equivocation_report_fails() {
+	new_test_ext().execute_with(|| {
+		let set_id = Grandpa::current_set_id();
+		let offender = grandpa_pair(2);
+		let proof = equivocation_proof(
+			set_id,
+			1,
+			&offender,
+			(H256::repeat_byte(1), 1),
+			(H256::repeat_byte(2), 1),
+		);
+		let key_owner_proof =
+			Historical::prove((sp_finality_grandpa::KEY_TYPE, offender.public().into())).unwrap();
+
+		assert_ok!(Grandpa::report_equivocation_unsigned(
+			RuntimeOrigin::none(),
+			Box::new(proof.clone()),
+			key_owner_proof.clone(),
+		));
+		assert!(Grandpa::report_equivocation_unsigned(
+			RuntimeOrigin::none(),
+			Box::new(proof),
+			key_owner_proof,
+		)
+		.is_err());
+	})
+}
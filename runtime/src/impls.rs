@@ -0,0 +1,720 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use crate::{Balance, ExtrinsicBaseWeight, RuntimeCall, MILLIUNIT};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::{Contains, Currency, Get, Imbalance, InstanceFilter, OnUnbalanced},
+	weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial},
+	RuntimeDebug,
+};
+use smallvec::smallvec;
+use sp_runtime::{Perbill, Percent};
+use sp_std::marker::PhantomData;
+
+type NegativeImbalance<R> = <pallet_balances::Pallet<R> as Currency<
+	<R as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// Credits the author of the current block, burning the amount if there is none.
+pub struct ToAuthor<R>(PhantomData<R>);
+
+impl<R> OnUnbalanced<NegativeImbalance<R>> for ToAuthor<R>
+where
+	R: pallet_balances::Config + pallet_authorship::Config,
+{
+	fn on_nonzero_unbalanced(amount: NegativeImbalance<R>) {
+		if let Some(author) = <pallet_authorship::Pallet<R>>::author() {
+			<pallet_balances::Pallet<R>>::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Splits transaction fees and tips between the treasury and the block author.
+///
+/// `FeesToTreasury` and `TipsToTreasury` are the treasury's shares, the author gets the rest.
+pub struct DealWithFees<R, FeesToTreasury, TipsToTreasury>(
+	PhantomData<(R, FeesToTreasury, TipsToTreasury)>,
+);
+
+impl<R, FeesToTreasury, TipsToTreasury> OnUnbalanced<NegativeImbalance<R>>
+	for DealWithFees<R, FeesToTreasury, TipsToTreasury>
+where
+	R: pallet_balances::Config + pallet_treasury::Config + pallet_authorship::Config,
+	pallet_treasury::Pallet<R>: OnUnbalanced<NegativeImbalance<R>>,
+	FeesToTreasury: Get<Percent>,
+	TipsToTreasury: Get<Percent>,
+{
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance<R>>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let fees_to_treasury = FeesToTreasury::get() * fees.peek();
+			let (mut to_treasury, mut to_author) = fees.split(fees_to_treasury);
+
+			if let Some(tips) = fees_then_tips.next() {
+				let tips_to_treasury = TipsToTreasury::get() * tips.peek();
+				let (tips_treasury, tips_author) = tips.split(tips_to_treasury);
+				to_treasury.subsume(tips_treasury);
+				to_author.subsume(tips_author);
+			}
+
+			<pallet_treasury::Pallet<R> as OnUnbalanced<_>>::on_unbalanced(to_treasury);
+			<ToAuthor<R> as OnUnbalanced<_>>::on_unbalanced(to_author);
+		}
+	}
+}
+
+/// Converts a weight into a fee, charging [`MILLIUNIT`] for an extrinsic of
+/// `ExtrinsicBaseWeight` and scaling linearly from there.
+///
+/// Combined with the fee multiplier, this keeps fees proportional to the block space a call
+/// takes instead of charging one unit per unit of weight.
+pub struct WeightToFee;
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		let p = MILLIUNIT;
+		let q = Balance::from(ExtrinsicBaseWeight::get().ref_time());
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			negative: false,
+			coeff_frac: Perbill::from_rational(p % q, q),
+			coeff_integer: p / q,
+		}]
+	}
+}
+
+/// The kinds of calls a proxy account may make on behalf of its delegator.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Encode,
+	Decode,
+	RuntimeDebug,
+	MaxEncodedLen,
+	scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+	/// Any call.
+	Any,
+	/// Any call that cannot move balances, kitties or claims to another account, or lock up
+	/// funds as a treasury or democracy deposit.
+	NonTransfer,
+	/// Only calls to the poe pallet.
+	PoeOnly,
+	/// Only calls to the kitties pallet.
+	KittiesOnly,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, c: &RuntimeCall) -> bool {
+		// Calls batched through the utility pallet are filtered one by one, so batches are
+		// allowed for the restricted proxies as well.
+		match self {
+			ProxyType::Any => true,
+			ProxyType::NonTransfer => !matches!(
+				c,
+				RuntimeCall::Balances(..) |
+					RuntimeCall::KittiesModule(
+						pallet_kitties::Call::transfer { .. } |
+							pallet_kitties::Call::transfer_from { .. } |
+							pallet_kitties::Call::transfer_batch { .. } |
+							pallet_kitties::Call::approve { .. } |
+							pallet_kitties::Call::set_approval_for_all { .. } |
+							pallet_kitties::Call::accept_swap { .. } |
+							pallet_kitties::Call::offer_lease { .. } |
+							pallet_kitties::Call::accept_lease { .. } |
+							pallet_kitties::Call::burn_batch { .. }
+					) | RuntimeCall::PoeModule(pallet_poe::Call::transfer_claim { .. }) |
+					RuntimeCall::Treasury(pallet_treasury::Call::propose_spend { .. }) |
+					RuntimeCall::Democracy(
+						pallet_democracy::Call::propose { .. } |
+							pallet_democracy::Call::second { .. }
+					)
+			),
+			ProxyType::PoeOnly => {
+				matches!(c, RuntimeCall::PoeModule(..) | RuntimeCall::Utility(..))
+			},
+			ProxyType::KittiesOnly => {
+				matches!(c, RuntimeCall::KittiesModule(..) | RuntimeCall::Utility(..))
+			},
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			_ => false,
+		}
+	}
+}
+
+/// Calls that stay available while pallets are paused or the chain is in safe mode.
+///
+/// Consensus and system calls keep the chain producing blocks, and governance calls keep the
+/// council and referenda able to lift the restrictions. Calls wrapped by utility, multisig or
+/// proxy are filtered again when they are dispatched.
+pub struct SafeModeWhitelist;
+
+impl Contains<RuntimeCall> for SafeModeWhitelist {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::System(..) |
+				RuntimeCall::Timestamp(..) |
+				RuntimeCall::Session(..) |
+				RuntimeCall::Grandpa(..) |
+				RuntimeCall::ValidatorSet(..) |
+				RuntimeCall::Council(..) |
+				RuntimeCall::CouncilMembership(..) |
+				RuntimeCall::Preimage(..) |
+				RuntimeCall::Scheduler(..) |
+				RuntimeCall::Democracy(..) |
+				RuntimeCall::Utility(..) |
+				RuntimeCall::Multisig(..) |
+				RuntimeCall::Proxy(..) |
+				RuntimeCall::Sudo(..)
+		)
+	}
+}
+
+#[cfg(test)]
+mod multiplier_tests {
+	use super::*;
+	use crate::{
+		AdjustmentVariable, BlockWeights, MinimumMultiplier, Runtime, System, TargetBlockFullness,
+		TransactionPayment,
+	};
+	use frame_support::{
+		dispatch::DispatchClass,
+		weights::{Weight, WeightToFee as WeightToFeeT},
+	};
+	use pallet_transaction_payment::Multiplier;
+	use sp_runtime::{
+		assert_eq_error_rate,
+		traits::{Convert, One, Zero},
+		BuildStorage, FixedPointNumber,
+	};
+
+	fn max_normal() -> Weight {
+		BlockWeights::get()
+			.get(DispatchClass::Normal)
+			.max_total
+			.unwrap_or_else(|| BlockWeights::get().max_block)
+	}
+
+	fn target() -> Weight {
+		TargetBlockFullness::get() * max_normal()
+	}
+
+	// The formula of `TargetedFeeAdjustment`, computed independently with floats.
+	fn truth_value_update(block_weight: Weight, previous: Multiplier) -> Multiplier {
+		let accuracy = Multiplier::accuracy() as f64;
+		let previous_float = previous.into_inner() as f64 / accuracy;
+		// Bump if it is zero.
+		let previous_float =
+			previous_float.max(MinimumMultiplier::get().into_inner() as f64 / accuracy);
+
+		let fullness = block_weight.ref_time() as f64 / max_normal().ref_time() as f64;
+		let target = target().ref_time() as f64 / max_normal().ref_time() as f64;
+		let v: f64 = AdjustmentVariable::get().to_float();
+		let diff = fullness - target;
+
+		let next_float = previous_float * (1.0 + (v * diff) + (v * diff).powi(2) / 2.0);
+		Multiplier::from_float(next_float)
+	}
+
+	fn run_with_system_weight<F>(w: Weight, assertions: F)
+	where
+		F: FnOnce(),
+	{
+		let mut t: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into();
+		t.execute_with(|| {
+			System::set_block_consumed_resources(w, 0);
+			assertions()
+		});
+	}
+
+	fn runtime_multiplier_update(fm: Multiplier) -> Multiplier {
+		<Runtime as pallet_transaction_payment::Config>::FeeMultiplierUpdate::convert(fm)
+	}
+
+	#[test]
+	fn base_extrinsic_costs_one_milliunit() {
+		assert_eq!(WeightToFee::weight_to_fee(&ExtrinsicBaseWeight::get()), MILLIUNIT);
+		assert_eq!(WeightToFee::weight_to_fee(&Weight::zero()), 0);
+	}
+
+	#[test]
+	fn multiplier_can_grow_from_zero() {
+		// If the multiplier were ever to reach zero it must still be able to grow again.
+		run_with_system_weight(target() * 101 / 100, || {
+			let next = runtime_multiplier_update(Multiplier::zero());
+			assert!(next > Multiplier::zero(), "{:?} !> zero", next);
+		})
+	}
+
+	#[test]
+	fn multiplier_matches_formula() {
+		let fm = Multiplier::saturating_from_rational(1, 2);
+		let test_set = vec![
+			(Weight::zero(), fm),
+			(Weight::from_ref_time(100), fm),
+			(Weight::from_ref_time(1000), fm),
+			(target(), fm),
+			(max_normal() / 2, fm),
+			(max_normal(), fm),
+		];
+		test_set.into_iter().for_each(|(w, fm)| {
+			run_with_system_weight(w, || {
+				assert_eq_error_rate!(
+					truth_value_update(w, fm),
+					runtime_multiplier_update(fm),
+					// Error is only 1 in 100^18.
+					Multiplier::from_inner(100),
+				);
+			})
+		})
+	}
+
+	#[test]
+	fn multiplier_stays_at_target() {
+		run_with_system_weight(target(), || {
+			let fm = Multiplier::saturating_from_rational(1, 2);
+			assert_eq!(runtime_multiplier_update(fm), fm);
+		})
+	}
+
+	#[test]
+	fn fees_rise_with_sustained_full_blocks_and_decay_after() {
+		let block_weight = max_normal();
+		let base_fee = TransactionPayment::weight_to_fee(ExtrinsicBaseWeight::get());
+
+		// One externality for the whole run, only the consumed weight changes per block.
+		run_with_system_weight(block_weight, || {
+			let mut fm = Multiplier::one();
+
+			// A day of full blocks keeps raising the multiplier.
+			for blocks in 0..crate::DAYS {
+				let next = runtime_multiplier_update(fm);
+				assert!(next > fm, "{:?} !> {:?} after {} full blocks", next, fm, blocks);
+				fm = next;
+			}
+			let peak = fm;
+			assert!(peak > Multiplier::saturating_from_integer(2));
+			assert!(peak.saturating_mul_int(base_fee) > 2 * base_fee);
+
+			// Empty blocks then let it decay, but never below the minimum.
+			System::set_block_consumed_resources(Weight::zero(), 0);
+			for blocks in 0..crate::DAYS {
+				let next = runtime_multiplier_update(fm);
+				assert!(next < fm, "{:?} !< {:?} after {} empty blocks", next, fm, blocks);
+				assert!(next >= MinimumMultiplier::get());
+				fm = next;
+			}
+			assert!(fm < peak);
+		})
+	}
+
+	#[test]
+	fn min_multiplier_is_respected() {
+		run_with_system_weight(Weight::zero(), || {
+			let next = runtime_multiplier_update(MinimumMultiplier::get());
+			assert_eq!(next, MinimumMultiplier::get());
+		})
+	}
+}
+
+#[cfg(test)]
+mod fee_tests {
+	use super::*;
+	use frame_support::{
+		parameter_types,
+		traits::{ConstU32, ConstU64, FindAuthor},
+		PalletId,
+	};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		BuildStorage, ConsensusEngineId,
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system,
+			Authorship: pallet_authorship,
+			Balances: pallet_balances,
+			Treasury: pallet_treasury,
+		}
+	);
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type RuntimeOrigin = RuntimeOrigin;
+		type RuntimeCall = RuntimeCall;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type RuntimeEvent = RuntimeEvent;
+		type BlockHashCount = ConstU64<250>;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = ConstU32<16>;
+	}
+
+	impl pallet_balances::Config for Test {
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type Balance = u64;
+		type RuntimeEvent = RuntimeEvent;
+		type DustRemoval = ();
+		type ExistentialDeposit = ConstU64<1>;
+		type AccountStore = System;
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+		pub const FeesToTreasury: Percent = Percent::from_percent(80);
+		pub const TipsToTreasury: Percent = Percent::from_percent(0);
+	}
+
+	impl pallet_treasury::Config for Test {
+		type Currency = Balances;
+		type ApproveOrigin = EnsureRoot<u64>;
+		type RejectOrigin = EnsureRoot<u64>;
+		type RuntimeEvent = RuntimeEvent;
+		type OnSlash = ();
+		type ProposalBond = ();
+		type ProposalBondMinimum = ();
+		type ProposalBondMaximum = ();
+		type SpendPeriod = ConstU64<2>;
+		type Burn = ();
+		type BurnDestination = ();
+		type PalletId = TreasuryPalletId;
+		type SpendFunds = ();
+		type MaxApprovals = ();
+		type WeightInfo = ();
+		type SpendOrigin = frame_support::traits::NeverEnsureOrigin<u64>;
+	}
+
+	pub struct OneAuthor;
+	impl FindAuthor<u64> for OneAuthor {
+		fn find_author<'a, I>(_: I) -> Option<u64>
+		where
+			I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+		{
+			Some(1)
+		}
+	}
+
+	impl pallet_authorship::Config for Test {
+		type FindAuthor = OneAuthor;
+		type UncleGenerations = ();
+		type FilterUncle = ();
+		type EventHandler = ();
+	}
+
+	type Fees = DealWithFees<Test, FeesToTreasury, TipsToTreasury>;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		// Both the author and the treasury start without balance.
+		pallet_balances::GenesisConfig::<Test>::default()
+			.assimilate_storage(&mut t)
+			.unwrap();
+		t.into()
+	}
+
+	#[test]
+	fn fees_and_tips_are_split() {
+		new_test_ext().execute_with(|| {
+			let fee = Balances::issue(10);
+			let tip = Balances::issue(20);
+
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 0);
+			assert_eq!(Balances::free_balance(1), 0);
+
+			Fees::on_unbalanceds(vec![fee, tip].into_iter());
+
+			// Fees go 80% to the treasury, tips go to the author.
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 8);
+			assert_eq!(Balances::free_balance(1), 22);
+			// Nothing was burned.
+			assert_eq!(Balances::total_issuance(), 30);
+		});
+	}
+
+	#[test]
+	fn fees_without_tip_are_split() {
+		new_test_ext().execute_with(|| {
+			Fees::on_unbalanceds(vec![Balances::issue(100)].into_iter());
+
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 80);
+			assert_eq!(Balances::free_balance(1), 20);
+		});
+	}
+
+	#[test]
+	fn split_keeps_issuance() {
+		new_test_ext().execute_with(|| {
+			// Rounding never creates or destroys balance.
+			Fees::on_unbalanceds(vec![Balances::issue(7), Balances::issue(3)].into_iter());
+
+			assert_eq!(
+				Balances::free_balance(Treasury::account_id()) + Balances::free_balance(1),
+				10
+			);
+			assert_eq!(Balances::total_issuance(), 10);
+		});
+	}
+}
+
+#[cfg(test)]
+mod proxy_tests {
+	use super::*;
+	use crate::{AccountId, SystemCall};
+
+	fn poe_call() -> RuntimeCall {
+		RuntimeCall::PoeModule(pallet_poe::Call::create_claim { claim: vec![0, 1] })
+	}
+
+	fn claim_transfer() -> RuntimeCall {
+		RuntimeCall::PoeModule(pallet_poe::Call::transfer_claim {
+			claim: vec![0, 1],
+			dest: AccountId::new([2; 32]),
+		})
+	}
+
+	fn kitty_call() -> RuntimeCall {
+		RuntimeCall::KittiesModule(pallet_kitties::Call::create {})
+	}
+
+	fn kitty_transfer_calls() -> Vec<RuntimeCall> {
+		let to = AccountId::new([2; 32]);
+		vec![
+			RuntimeCall::KittiesModule(pallet_kitties::Call::transfer {
+				kitty_id: 0,
+				new_owner: to.clone(),
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::transfer_from {
+				from: AccountId::new([1; 32]),
+				to: to.clone(),
+				kitty_id: 0,
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::transfer_batch {
+				kitty_ids: vec![0],
+				new_owner: to.clone(),
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::approve {
+				kitty_id: 0,
+				spender: to.clone(),
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::set_approval_for_all {
+				operator: to.clone(),
+				approved: true,
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::accept_swap {
+				kitty_id: 0,
+				counter_kitty_id: 1,
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::offer_lease {
+				kitty_id: 0,
+				borrower: to,
+				fee: 1,
+				duration: 10,
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::accept_lease { kitty_id: 0 }),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::burn_batch { kitty_ids: vec![0] }),
+		]
+	}
+
+	fn deposit_calls() -> Vec<RuntimeCall> {
+		vec![
+			RuntimeCall::Treasury(pallet_treasury::Call::propose_spend {
+				value: 1,
+				beneficiary: AccountId::new([2; 32]).into(),
+			}),
+			RuntimeCall::Democracy(pallet_democracy::Call::second { proposal: 0 }),
+		]
+	}
+
+	fn balance_transfer() -> RuntimeCall {
+		RuntimeCall::Balances(pallet_balances::Call::transfer {
+			dest: AccountId::new([2; 32]).into(),
+			value: 1,
+		})
+	}
+
+	fn remark() -> RuntimeCall {
+		RuntimeCall::System(SystemCall::remark { remark: vec![] })
+	}
+
+	fn batch(calls: Vec<RuntimeCall>) -> RuntimeCall {
+		RuntimeCall::Utility(pallet_utility::Call::batch { calls })
+	}
+
+	#[test]
+	fn any_allows_every_call() {
+		for call in [poe_call(), claim_transfer(), kitty_call(), balance_transfer(), remark()] {
+			assert!(ProxyType::Any.filter(&call));
+		}
+	}
+
+	#[test]
+	fn non_transfer_blocks_value_transfers() {
+		assert!(ProxyType::NonTransfer.filter(&poe_call()));
+		assert!(ProxyType::NonTransfer.filter(&remark()));
+		assert!(!ProxyType::NonTransfer.filter(&claim_transfer()));
+		assert!(!ProxyType::NonTransfer.filter(&balance_transfer()));
+		for call in kitty_transfer_calls().into_iter().chain(deposit_calls()) {
+			assert!(!ProxyType::NonTransfer.filter(&call), "{:?} must be blocked", call);
+		}
+	}
+
+	#[test]
+	fn non_transfer_allows_kitty_calls_that_keep_ownership() {
+		let kitty_calls = [
+			kitty_call(),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::set_kitty_metadata {
+				kitty_id: 0,
+				name: b"Tom".to_vec(),
+				metadata_uri: vec![],
+			}),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::cancel_approval { kitty_id: 0 }),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::return_lease { kitty_id: 0 }),
+			RuntimeCall::KittiesModule(pallet_kitties::Call::cancel_swap { kitty_id: 0 }),
+		];
+		for call in kitty_calls {
+			assert!(ProxyType::NonTransfer.filter(&call), "{:?} must be allowed", call);
+		}
+	}
+
+	#[test]
+	fn poe_only_allows_poe_calls() {
+		assert!(ProxyType::PoeOnly.filter(&poe_call()));
+		assert!(ProxyType::PoeOnly.filter(&claim_transfer()));
+		assert!(ProxyType::PoeOnly.filter(&batch(vec![poe_call()])));
+		assert!(!ProxyType::PoeOnly.filter(&kitty_call()));
+		assert!(!ProxyType::PoeOnly.filter(&balance_transfer()));
+		assert!(!ProxyType::PoeOnly.filter(&remark()));
+	}
+
+	#[test]
+	fn kitties_only_allows_kitty_calls() {
+		assert!(ProxyType::KittiesOnly.filter(&kitty_call()));
+		assert!(ProxyType::KittiesOnly.filter(&batch(vec![kitty_call()])));
+		assert!(!ProxyType::KittiesOnly.filter(&poe_call()));
+		assert!(!ProxyType::KittiesOnly.filter(&balance_transfer()));
+		assert!(!ProxyType::KittiesOnly.filter(&remark()));
+	}
+
+	#[test]
+	fn only_any_is_a_superset_of_other_types() {
+		let all =
+			[ProxyType::Any, ProxyType::NonTransfer, ProxyType::PoeOnly, ProxyType::KittiesOnly];
+		for x in all {
+			assert!(ProxyType::Any.is_superset(&x));
+			assert!(x.is_superset(&x));
+		}
+		assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::PoeOnly));
+		assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::KittiesOnly));
+		assert!(!ProxyType::PoeOnly.is_superset(&ProxyType::Any));
+	}
+}
+
+#[cfg(test)]
+mod call_filter_tests {
+	use super::*;
+	use crate::{CallFilter, Runtime, RuntimeOrigin, SystemCall};
+	use frame_support::assert_ok;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+
+	fn poe_call() -> RuntimeCall {
+		RuntimeCall::PoeModule(pallet_poe::Call::create_claim { claim: vec![0, 1] })
+	}
+
+	fn kitty_call() -> RuntimeCall {
+		RuntimeCall::KittiesModule(pallet_kitties::Call::create {})
+	}
+
+	fn timestamp_call() -> RuntimeCall {
+		RuntimeCall::Timestamp(pallet_timestamp::Call::set { now: 0 })
+	}
+
+	fn remark() -> RuntimeCall {
+		RuntimeCall::System(SystemCall::remark { remark: vec![] })
+	}
+
+	#[test]
+	fn safe_mode_keeps_consensus_and_governance_calls() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CallFilter::enter_safe_mode(RuntimeOrigin::root()));
+
+			assert!(CallFilter::contains(&timestamp_call()));
+			assert!(CallFilter::contains(&remark()));
+			assert!(CallFilter::contains(&RuntimeCall::Democracy(
+				pallet_democracy::Call::clear_public_proposals {}
+			)));
+			assert!(!CallFilter::contains(&poe_call()));
+			assert!(!CallFilter::contains(&kitty_call()));
+
+			assert_ok!(CallFilter::exit_safe_mode(RuntimeOrigin::root()));
+			assert!(CallFilter::contains(&poe_call()));
+			assert!(CallFilter::contains(&kitty_call()));
+		})
+	}
+
+	#[test]
+	fn pausing_kitties_leaves_poe_running() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CallFilter::pause(RuntimeOrigin::root(), b"KittiesModule".to_vec(), None));
+
+			assert!(!CallFilter::contains(&kitty_call()));
+			assert!(CallFilter::contains(&poe_call()));
+		})
+	}
+}
@@ -6,6 +6,9 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+mod impls;
+pub use impls::{DealWithFees, ProxyType, SafeModeWhitelist, WeightToFee};
+
 // use frame_benchmarking::baseline::mock::RuntimeEvent;
 use pallet_grandpa::{
     fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
@@ -13,13 +16,15 @@ use pallet_grandpa::{
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use codec::Encode;
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
     traits::{
-        AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+        self, AccountIdLookup, BlakeTwo256, Block as BlockT, Bounded, IdentifyAccount, NumberFor,
+        OpaqueKeys, Verify,
     },
-    transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, MultiSignature,
+    transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
+    ApplyExtrinsicResult, FixedPointNumber, MultiSignature, SaturatedConversion,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -30,7 +35,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
     construct_runtime, parameter_types,
     traits::{
-        ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+        ConstU128, ConstU16, ConstU32, ConstU64, ConstU8, EitherOfDiverse, EqualPrivilegeOnly,
+        KeyOwnerProofSystem, Randomness, StorageInfo,
     },
     weights::{
         constants::{
@@ -38,15 +44,16 @@ pub use frame_support::{
         },
         IdentityFee, Weight,
     },
-    StorageValue,
+    PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
-use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
+use frame_system::EnsureRoot;
+use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{Perbill, Percent, Permill, Perquintill};
 
 /// Import the template pallet.
 pub use pallet_template;
@@ -57,6 +64,12 @@ pub use pallet_poe;
 /// Import the kitties pallet.
 pub use pallet_kitties;
 
+/// Import the validator-set pallet.
+pub use pallet_validator_set;
+
+/// Import the call-filter pallet.
+pub use pallet_call_filter;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -75,6 +88,9 @@ pub type Index = u32;
 
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
+
+/// Index of a kitty in pallet-kitties.
+pub type KittyIndex = u32;
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -111,7 +127,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
     //   the compatible custom types.
-    spec_version: 100,
+    spec_version: 101,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 1,
@@ -156,13 +172,15 @@ parameter_types! {
 		::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
 	pub const SS58Prefix: u8 = 42;
 	pub const KittyReserve: u64 = 1_000;
+	pub const KittyMetadataDepositPerByte: u64 = 10;
 }
 
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Config for Runtime {
-    /// The basic call filter to use in dispatchable.
-    type BaseCallFilter = frame_support::traits::Everything;
+    /// The basic call filter to use in dispatchable, pallets or calls can be paused through
+    /// pallet-call-filter.
+    type BaseCallFilter = CallFilter;
     /// Block & extrinsics weights: base values and limits.
     type BlockWeights = BlockWeights;
     /// The maximum length of a block (in bytes).
@@ -216,14 +234,60 @@ impl pallet_randomness_collective_flip::Config for Runtime {}
 
 impl pallet_aura::Config for Runtime {
     type AuthorityId = AuraId;
-    type DisabledValidators = ();
+    type DisabledValidators = Session;
     type MaxAuthorities = ConstU32<32>;
 }
 
+parameter_types! {
+	/// Validator set changes are queued at the next session and applied the one after.
+	pub const SessionPeriod: BlockNumber = 10 * MINUTES;
+	pub const SessionOffset: BlockNumber = 0;
+	/// Number of blocks an equivocation report stays valid in the transaction pool.
+	pub const ReportLongevity: u64 = DAYS as u64;
+}
+
+impl pallet_session::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type ValidatorId = <Self as frame_system::Config>::AccountId;
+    type ValidatorIdOf = pallet_validator_set::ValidatorOf<Self>;
+    type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+    type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+    type Keys = opaque::SessionKeys;
+    type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_session::historical::Config for Runtime {
+    type FullIdentification = AccountId;
+    type FullIdentificationOf = pallet_validator_set::ValidatorOf<Self>;
+}
+
+impl pallet_authorship::Config for Runtime {
+    type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+    type UncleGenerations = ConstU32<0>;
+    type FilterUncle = ();
+    type EventHandler = ();
+}
+
+impl pallet_offences::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+    type OnOffenceHandler = ValidatorSet;
+}
+
+/// Configure the pallet-validator-set in pallets/validator-set.
+impl pallet_validator_set::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type AddRemoveOrigin = EnsureRootOrHalfCouncil;
+    type MinAuthorities = ConstU32<1>;
+    type MaxValidators = ConstU32<32>;
+}
+
 impl pallet_grandpa::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
 
-    type KeyOwnerProofSystem = ();
+    type KeyOwnerProofSystem = Historical;
 
     type KeyOwnerProof =
     <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
@@ -233,7 +297,11 @@ impl pallet_grandpa::Config for Runtime {
         GrandpaId,
     )>>::IdentificationTuple;
 
-    type HandleEquivocation = ();
+    type HandleEquivocation = pallet_grandpa::EquivocationHandler<
+        Self::KeyOwnerIdentification,
+        Offences,
+        ReportLongevity,
+    >;
 
     type WeightInfo = ();
     type MaxAuthorities = ConstU32<32>;
@@ -250,6 +318,16 @@ impl pallet_timestamp::Config for Runtime {
 /// Existential deposit.
 pub const EXISTENTIAL_DEPOSIT: u128 = 500;
 
+/// One token, and its fractions used to price fees.
+pub const UNIT: Balance = 1_000_000_000_000;
+pub const MILLIUNIT: Balance = UNIT / 1_000;
+pub const MICROUNIT: Balance = MILLIUNIT / 1_000;
+
+/// Deposit reserved for storing `items` storage items taking `bytes` bytes in total.
+pub const fn deposit(items: u32, bytes: u32) -> Balance {
+    items as Balance * 100 * MILLIUNIT + bytes as Balance * 100 * MICROUNIT
+}
+
 impl pallet_balances::Config for Runtime {
     type MaxLocks = ConstU32<50>;
     type MaxReserves = ();
@@ -265,16 +343,239 @@ impl pallet_balances::Config for Runtime {
 }
 
 parameter_types! {
-	pub FeeMultiplier: Multiplier = Multiplier::one();
+	/// Blocks filled to this share of the normal dispatch capacity (`NORMAL_DISPATCH_RATIO` of
+	/// the block weight) keep fees steady; fuller blocks raise them, emptier ones lower them.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	/// A day of full blocks roughly doubles the fee multiplier.
+	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(75, 1_000_000);
+	/// Fees never drop below a tenth of their base level.
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 10u128);
+	pub MaximumMultiplier: Multiplier = Bounded::max_value();
+}
+
+parameter_types! {
+	/// Share of transaction fees paid into the treasury, the block author gets the rest.
+	pub const FeesToTreasury: Percent = Percent::from_percent(80);
+	/// Share of tips paid into the treasury, the block author gets the rest.
+	pub const TipsToTreasury: Percent = Percent::from_percent(0);
 }
 
 impl pallet_transaction_payment::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
-    type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+    type OnChargeTransaction =
+        CurrencyAdapter<Balances, DealWithFees<Runtime, FeesToTreasury, TipsToTreasury>>;
     type OperationalFeeMultiplier = ConstU8<5>;
-    type WeightToFee = IdentityFee<Balance>;
+    type WeightToFee = WeightToFee;
     type LengthToFee = IdentityFee<Balance>;
-    type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
+    type FeeMultiplierUpdate = TargetedFeeAdjustment<
+        Self,
+        TargetBlockFullness,
+        AdjustmentVariable,
+        MinimumMultiplier,
+        MaximumMultiplier,
+    >;
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = UNIT;
+	pub const SpendPeriod: BlockNumber = DAYS;
+	pub const Burn: Permill = Permill::zero();
+}
+
+impl pallet_treasury::Config for Runtime {
+    type Currency = Balances;
+    type ApproveOrigin = EnsureRootOrHalfCouncil;
+    type RejectOrigin = EnsureRootOrHalfCouncil;
+    type RuntimeEvent = RuntimeEvent;
+    type OnSlash = Treasury;
+    type ProposalBond = ProposalBond;
+    type ProposalBondMinimum = ProposalBondMinimum;
+    type ProposalBondMaximum = ();
+    type SpendPeriod = SpendPeriod;
+    type Burn = Burn;
+    type BurnDestination = ();
+    type PalletId = TreasuryPalletId;
+    type SpendFunds = ();
+    type MaxApprovals = ConstU32<100>;
+    type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+    type SpendOrigin = frame_support::traits::NeverEnsureOrigin<Balance>;
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+
+/// Root, or more than half of the council.
+pub type EnsureRootOrHalfCouncil = EitherOfDiverse<
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
+>;
+
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+    type RuntimeOrigin = RuntimeOrigin;
+    type Proposal = RuntimeCall;
+    type RuntimeEvent = RuntimeEvent;
+    type MotionDuration = CouncilMotionDuration;
+    type MaxProposals = CouncilMaxProposals;
+    type MaxMembers = CouncilMaxMembers;
+    type DefaultVote = pallet_collective::PrimeDefaultVote;
+    type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// Council seats are managed by the council itself.
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type AddOrigin = EnsureRootOrHalfCouncil;
+    type RemoveOrigin = EnsureRootOrHalfCouncil;
+    type SwapOrigin = EnsureRootOrHalfCouncil;
+    type ResetOrigin = EnsureRootOrHalfCouncil;
+    type PrimeOrigin = EnsureRootOrHalfCouncil;
+    type MembershipInitialized = Council;
+    type MembershipChanged = Council;
+    type MaxMembers = CouncilMaxMembers;
+    type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = UNIT;
+	pub const PreimageByteDeposit: Balance = MILLIUNIT;
+}
+
+impl pallet_preimage::Config for Runtime {
+    type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type ManagerOrigin = EnsureRoot<AccountId>;
+    type BaseDeposit = PreimageBaseDeposit;
+    type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeOrigin = RuntimeOrigin;
+    type PalletsOrigin = OriginCaller;
+    type RuntimeCall = RuntimeCall;
+    type MaximumWeight = MaximumSchedulerWeight;
+    type ScheduleOrigin = EnsureRoot<AccountId>;
+    type MaxScheduledPerBlock = ConstU32<50>;
+    type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+    type OriginPrivilegeCmp = EqualPrivilegeOnly;
+    type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+	pub const VotingPeriod: BlockNumber = 7 * DAYS;
+	pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+	pub const EnactmentPeriod: BlockNumber = DAYS;
+	pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+	pub const MinimumDeposit: Balance = 100 * UNIT;
+}
+
+/// Passed referenda, runtime upgrades included, are dispatched as root by the scheduler.
+/// The council proposes external referenda and can fast-track or cancel them.
+impl pallet_democracy::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type EnactmentPeriod = EnactmentPeriod;
+    type LaunchPeriod = LaunchPeriod;
+    type VotingPeriod = VotingPeriod;
+    type VoteLockingPeriod = EnactmentPeriod;
+    type MinimumDeposit = MinimumDeposit;
+    type ExternalOrigin = EnsureRootOrHalfCouncil;
+    type ExternalMajorityOrigin = EnsureRootOrHalfCouncil;
+    type ExternalDefaultOrigin =
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 1>;
+    type FastTrackOrigin =
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
+    type InstantOrigin =
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 1>;
+    type InstantAllowed = frame_support::traits::ConstBool<true>;
+    type FastTrackVotingPeriod = FastTrackVotingPeriod;
+    type CancellationOrigin =
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
+    type BlacklistOrigin = EnsureRoot<AccountId>;
+    type CancelProposalOrigin = EitherOfDiverse<
+        EnsureRoot<AccountId>,
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 1>,
+    >;
+    type VetoOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+    type CooloffPeriod = CooloffPeriod;
+    type Slash = Treasury;
+    type Scheduler = Scheduler;
+    type PalletsOrigin = OriginCaller;
+    type MaxVotes = ConstU32<100>;
+    type WeightInfo = pallet_democracy::weights::SubstrateWeight<Runtime>;
+    type MaxProposals = ConstU32<100>;
+    type Preimages = Preimage;
+    type MaxDeposits = ConstU32<100>;
+    type MaxBlacklisted = ConstU32<100>;
+}
+
+impl pallet_utility::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type PalletsOrigin = OriginCaller;
+    type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+	pub const DepositBase: Balance = deposit(1, 88);
+	// Additional storage item size of 32 bytes.
+	pub const DepositFactor: Balance = deposit(0, 32);
+}
+
+impl pallet_multisig::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Currency = Balances;
+    type DepositBase = DepositBase;
+    type DepositFactor = DepositFactor;
+    type MaxSignatories = ConstU16<100>;
+    type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = deposit(1, 8);
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+	pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+}
+
+impl pallet_proxy::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Currency = Balances;
+    type ProxyType = ProxyType;
+    type ProxyDepositBase = ProxyDepositBase;
+    type ProxyDepositFactor = ProxyDepositFactor;
+    type MaxProxies = ConstU32<32>;
+    type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+    type MaxPending = ConstU32<32>;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+/// Configure the pallet-call-filter in pallets/call-filter.
+impl pallet_call_filter::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type PauseOrigin = EnsureRootOrHalfCouncil;
+    type WhitelistedCalls = SafeModeWhitelist;
+    type MaxNameLength = ConstU32<64>;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -290,15 +591,83 @@ impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
 	type OverarchingCall = RuntimeCall;
 }
 
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+	where
+		RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(RuntimeCall, <UncheckedExtrinsic as traits::Extrinsic>::SignaturePayload)> {
+		let tip = 0;
+		// take the biggest period possible.
+		let period =
+			BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number()
+			.saturated_into::<u64>()
+			// The `System::block_number` is initialized with `n+1`,
+			// so the actual block number is `n`.
+			.saturating_sub(1);
+		let era = generic::Era::mortal(period, current_block);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(era),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+		);
+		let raw_payload = SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let address = sp_runtime::MultiAddress::Id(account);
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (address, signature, extra)))
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+parameter_types! {
+	pub const TemplateUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
+    type AuthorityId = pallet_template::crypto::TemplateAuthId;
+    type UnsignedInterval = ConstU32<5>;
+    type UnsignedPriority = TemplateUnsignedPriority;
+    type MaxAuthorities = ConstU32<32>;
+    type MaxPrices = ConstU32<64>;
+    type OffchainInterval = ConstU32<5>;
+    type MaxHistory = ConstU32<10>;
+}
+
+/// Receives the old global `Something` value of pallet-template, see its v1 migration.
+///
+/// Chains without a sudo key (like the local testnet) keep the value under
+/// `pallet_template::migrations::v1::unowned_account`.
+pub struct TemplateMigrationOwner;
+
+impl frame_support::traits::Get<Option<AccountId>> for TemplateMigrationOwner {
+    fn get() -> Option<AccountId> {
+        Sudo::key()
+    }
 }
 
 /// Configure the pallet-poe in pallets/poe.
 impl pallet_poe::Config for Runtime {
 	type MaxClaimLength = ConstU32<512>;
+	type MaxContentLength = ConstU32<1024>;
 	type RuntimeEvent = RuntimeEvent;
+	type ArbiterOrigin = EnsureRootOrHalfCouncil;
 	type WeightInfo = pallet_poe::weights::SubstrateWeight<Runtime>;
 }
 
@@ -306,10 +675,23 @@ impl pallet_poe::Config for Runtime {
 impl pallet_kitties::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Randomness = RandomnessCollectiveFlip;
-    type KittyIndex = u32;
+    type KittyIndex = KittyIndex;
     type MaxKittyIndexLength = ConstU32<64>;
     type KittyReserve = KittyReserve;
     type Currency = Balances;
+    type MaxNameLength = ConstU32<32>;
+    type MaxMetadataUriLength = ConstU32<256>;
+    type MetadataDepositPerByte = KittyMetadataDepositPerByte;
+    // RandomnessCollectiveFlip的随机数取自最近81个区块, 揭示需等待其全部产生于提交之后
+    type RevealDelay = ConstU32<82>;
+    type RevealWindow = ConstU32<20>;
+    type MaxLeaseDuration = ConstU32<{ 7 * DAYS }>;
+    type MaxLeasesPerBlock = ConstU32<16>;
+    type MaxTotalKitties = ConstU32<1_000_000>;
+    // 与RevealDelay相同, 结算对战使用的随机数需全部产生于接受挑战之后
+    type BattleDelay = ConstU32<82>;
+    type MaxBatchSize = ConstU32<50>;
+    type MaxSwapDuration = ConstU32<{ 7 * DAYS }>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -323,10 +705,28 @@ construct_runtime!(
 		System: frame_system,
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip,
 		Timestamp: pallet_timestamp,
+		// Session reads the genesis validators from ValidatorSet and must initialize the
+		// consensus authorities before Aura and Grandpa.
+		Authorship: pallet_authorship,
+		ValidatorSet: pallet_validator_set,
+		Session: pallet_session,
+		Historical: pallet_session::historical,
+		Offences: pallet_offences,
 		Aura: pallet_aura,
 		Grandpa: pallet_grandpa,
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
+		Treasury: pallet_treasury,
+		// Governance: the council and public referenda replace sudo outside of dev chains.
+		Council: pallet_collective::<Instance1>,
+		CouncilMembership: pallet_membership::<Instance1>,
+		Preimage: pallet_preimage,
+		Scheduler: pallet_scheduler,
+		Democracy: pallet_democracy,
+		Utility: pallet_utility,
+		Multisig: pallet_multisig,
+		Proxy: pallet_proxy,
+		CallFilter: pallet_call_filter,
 		Sudo: pallet_sudo,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
@@ -358,6 +758,14 @@ pub type UncheckedExtrinsic =
 generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Storage migrations applied on runtime upgrade, each one only runs while the pallet's on-chain
+/// storage version is older than the one it migrates to. Remove them once every chain ran them.
+type Migrations = (
+    pallet_template::migrations::v1::MigrateToV1<Runtime, TemplateMigrationOwner>,
+    pallet_poe::migrations::v1::MigrateToV1<Runtime>,
+    pallet_kitties::migrations::v1::MigrateToV1<Runtime>,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -365,6 +773,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -473,23 +882,27 @@ impl_runtime_apis! {
 		}
 
 		fn submit_report_equivocation_unsigned_extrinsic(
-			_equivocation_proof: fg_primitives::EquivocationProof<
+			equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
 				NumberFor<Block>,
 			>,
-			_key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+			key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
 		) -> Option<()> {
-			None
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
 		}
 
 		fn generate_key_ownership_proof(
 			_set_id: fg_primitives::SetId,
-			_authority_id: GrandpaId,
+			authority_id: GrandpaId,
 		) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-			// NOTE: this is the only implementation possible since we've
-			// defined our key owner proof type as a bottom type (i.e. a type
-			// with no values).
-			None
+			Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(fg_primitives::OpaqueKeyOwnershipProof::new)
 		}
 	}
 
@@ -531,6 +944,30 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, KittyIndex> for Runtime {
+		fn kitty(kitty_id: KittyIndex) -> Option<pallet_kitties::KittyInfo<AccountId, KittyIndex>> {
+			KittiesModule::kitty_info(kitty_id)
+		}
+		fn kitties_owned_by(
+			owner: AccountId,
+			page: u32,
+			page_size: Option<u32>,
+			start_after: Option<KittyIndex>,
+		) -> Vec<pallet_kitties::KittyInfo<AccountId, KittyIndex>> {
+			KittiesModule::kitties_owned_by(&owner, page, page_size, start_after)
+		}
+		fn kitties_listed(
+			page: u32,
+			page_size: Option<u32>,
+			start_after: Option<KittyIndex>,
+		) -> Vec<pallet_kitties::KittyInfo<AccountId, KittyIndex>> {
+			KittiesModule::kitties_listed(page, page_size, start_after)
+		}
+		fn total_supply() -> u32 {
+			KittiesModule::kitty_count()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -629,4 +1066,172 @@ mod tests {
             whitelist.contains("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7")
         );
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod equivocation_tests {
+    use super::*;
+    use codec::Decode;
+    use fg_primitives::runtime_decl_for_GrandpaApi::GrandpaApi;
+    use frame_support::{
+        assert_ok,
+        unsigned::{TransactionSource, ValidateUnsigned},
+    };
+    use pallet_validator_set::test_utils;
+    use sp_core::offchain::{testing::TestTransactionPoolExt, TransactionPoolExt};
+    use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+    use sp_runtime::traits::Dispatchable;
+
+    const VALIDATORS: [Sr25519Keyring; 3] =
+        [Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie];
+
+    /// The GRANDPA session key of a validator.
+    fn grandpa_key(who: Sr25519Keyring) -> Ed25519Keyring {
+        match who {
+            Sr25519Keyring::Alice => Ed25519Keyring::Alice,
+            Sr25519Keyring::Bob => Ed25519Keyring::Bob,
+            Sr25519Keyring::Charlie => Ed25519Keyring::Charlie,
+            _ => unreachable!("not a test validator"),
+        }
+    }
+
+    /// Genesis with Alice, Bob and Charlie as validators, wired through the
+    /// validator set, session and historical pallets as on a real chain.
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let mut storage =
+            frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+        pallet_validator_set::GenesisConfig::<Runtime> {
+            initial_validators: VALIDATORS.iter().map(|v| v.to_account_id()).collect(),
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+        pallet_session::GenesisConfig::<Runtime> {
+            keys: VALIDATORS
+                .iter()
+                .map(|v| {
+                    let keys = opaque::SessionKeys {
+                        aura: v.public().into(),
+                        grandpa: grandpa_key(*v).public().into(),
+                    };
+                    (v.to_account_id(), v.to_account_id(), keys)
+                })
+                .collect(),
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        let mut ext = sp_io::TestExternalities::new(storage);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    /// Two prevotes signed by `offender` for different blocks in round 1.
+    fn equivocation_proof(
+        set_id: fg_primitives::SetId,
+        offender: Ed25519Keyring,
+    ) -> fg_primitives::EquivocationProof<Hash, BlockNumber> {
+        test_utils::equivocation_proof(
+            set_id,
+            1,
+            &offender.pair(),
+            (Hash::repeat_byte(1), 1),
+            (Hash::repeat_byte(2), 1),
+        )
+    }
+
+    #[test]
+    fn grandpa_equivocation_report_removes_offender() {
+        new_test_ext().execute_with(|| {
+            let offender = Sr25519Keyring::Bob;
+            let set_id = Grandpa::current_set_id();
+            let authority: GrandpaId = grandpa_key(offender).public().into();
+
+            // The proof the node obtains through the `GrandpaApi` runtime API.
+            let opaque_proof =
+                <Runtime as GrandpaApi<Block>>::generate_key_ownership_proof(set_id, authority.clone())
+                    .expect("Bob is a validator in the current session");
+            let key_owner_proof = opaque_proof.decode().expect("proof encoded by the runtime");
+            assert_eq!(
+                key_owner_proof,
+                Historical::prove((fg_primitives::KEY_TYPE, authority)).unwrap()
+            );
+
+            let call = pallet_grandpa::Call::<Runtime>::report_equivocation_unsigned {
+                equivocation_proof: Box::new(equivocation_proof(set_id, grandpa_key(offender))),
+                key_owner_proof,
+            };
+
+            // Accepted into the pool as an unsigned transaction, then dispatched without origin.
+            assert_ok!(Grandpa::validate_unsigned(TransactionSource::Local, &call));
+            assert_ok!(RuntimeCall::Grandpa(call).dispatch(RuntimeOrigin::none()));
+
+            // The offence went through `EquivocationHandler` and `Offences` to the validator set.
+            assert_eq!(pallet_offences::Reports::<Runtime>::iter().count(), 1);
+            System::assert_has_event(
+                pallet_validator_set::Event::OffenderRemoved {
+                    validator: offender.to_account_id(),
+                }
+                .into(),
+            );
+            assert_eq!(
+                ValidatorSet::validators().into_inner(),
+                vec![Sr25519Keyring::Alice.to_account_id(), Sr25519Keyring::Charlie.to_account_id()]
+            );
+            // Bob is index 1 of the current session and is disabled right away.
+            assert_eq!(Session::disabled_validators(), vec![1]);
+        })
+    }
+
+    /// The path a GRANDPA voter takes on the node: both runtime API calls, the
+    /// unsigned extrinsic landing in the pool, and the block author applying it.
+    #[test]
+    fn grandpa_equivocation_report_submitted_through_runtime_api() {
+        let (pool, pool_state) = TestTransactionPoolExt::new();
+        let mut ext = new_test_ext();
+        ext.register_extension(TransactionPoolExt::new(pool));
+        ext.execute_with(|| {
+            let offender = Sr25519Keyring::Charlie;
+            let set_id = <Runtime as GrandpaApi<Block>>::current_set_id();
+            let authority: GrandpaId = grandpa_key(offender).public().into();
+
+            let key_owner_proof =
+                <Runtime as GrandpaApi<Block>>::generate_key_ownership_proof(set_id, authority)
+                    .expect("Charlie is a validator in the current session");
+            assert_eq!(
+                <Runtime as GrandpaApi<Block>>::submit_report_equivocation_unsigned_extrinsic(
+                    equivocation_proof(set_id, grandpa_key(offender)),
+                    key_owner_proof,
+                ),
+                Some(())
+            );
+
+            // Exactly one unsigned report was queued in the pool.
+            let tx = {
+                let mut state = pool_state.write();
+                assert_eq!(state.transactions.len(), 1);
+                state.transactions.pop().unwrap()
+            };
+            let xt = UncheckedExtrinsic::decode(&mut &tx[..]).unwrap();
+            assert!(xt.signature.is_none());
+            assert!(matches!(
+                xt.function,
+                RuntimeCall::Grandpa(pallet_grandpa::Call::report_equivocation_unsigned { .. })
+            ));
+
+            // Included in the next block; the offence reaches the validator set.
+            assert_ok!(Executive::apply_extrinsic(xt).expect("report is a valid transaction"));
+            assert_eq!(pallet_offences::Reports::<Runtime>::iter().count(), 1);
+            System::assert_has_event(
+                pallet_validator_set::Event::OffenderRemoved {
+                    validator: offender.to_account_id(),
+                }
+                .into(),
+            );
+            assert_eq!(
+                ValidatorSet::validators().into_inner(),
+                vec![Sr25519Keyring::Alice.to_account_id(), Sr25519Keyring::Bob.to_account_id()]
+            );
+            assert_eq!(Session::disabled_validators(), vec![2]);
+        })
+    }
+}